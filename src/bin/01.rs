@@ -8,9 +8,11 @@ pub fn part_one(input: &str) -> Option<i32> {
             .split_whitespace()
             .filter_map(|n| n.parse().ok())
             .collect();
-        (first.push(nums[0]), second.push(nums[1]));
+        first.push(nums[0]);
+        second.push(nums[1]);
     }
-    (first.sort(), second.sort());
+    first.sort();
+    second.sort();
     let sum = first.iter().zip(second.iter())
         .map(|(a, b)| (b-a).abs())
         .sum();
@@ -24,7 +26,8 @@ pub fn part_two(input: &str) -> Option<i32> {
             .split_whitespace()
             .filter_map(|n| n.parse().ok())
             .collect();
-        (first.push(nums[0]), second.push(nums[1]));
+        first.push(nums[0]);
+        second.push(nums[1]);
     }
     first.sort();
 
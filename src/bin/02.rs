@@ -29,8 +29,8 @@ pub fn part_two(input: &str) -> Option<i32> {
 pub fn core(levels: &[i32]) -> bool {
     let diffs = levels.windows(2).map(|w| w[0] - w[1]);
 
-    let in_range_decreasing = diffs.clone().all(|x| 1 <= x && x <= 3);
-    let in_range_increasing = diffs.clone().all(|x| -3 <= x && x <= -1 );
+    let in_range_decreasing = diffs.clone().all(|x| (1..=3).contains(&x));
+    let in_range_increasing = diffs.clone().all(|x| (-3..=-1).contains(&x));
 
     in_range_decreasing || in_range_increasing
 }
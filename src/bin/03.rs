@@ -1,45 +1,88 @@
-use regex::Regex;
 advent_of_code::solution!(3);
 
-pub fn part_one(input: &str) -> Option<i32> {
+pub fn part_one(input: &str) -> Option<u32> {
+    let sum = Instructions::new(input)
+        .map(|instruction| match instruction {
+            Instruction::Mul(a, b) => a * b,
+            Instruction::Do | Instruction::Dont => 0,
+        })
+        .sum();
+    Some(sum)
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    let mut enabled = true;
     let mut sum = 0;
-    for line in input.lines() {
-        sum += p1(&line);
+    // the input is one program, a `don't()` stays in effect across line breaks.
+    for instruction in Instructions::new(input) {
+        match instruction {
+            Instruction::Do => enabled = true,
+            Instruction::Dont => enabled = false,
+            Instruction::Mul(a, b) if enabled => sum += a * b,
+            Instruction::Mul(a, b) => advent_of_code::debug!("skipping mul({a},{b})"),
+        }
     }
     Some(sum)
 }
 
-pub fn part_two(input: &str) -> Option<i32> {
-    let mut sum = 0;
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split("do()").collect();
-        for (index, part) in parts.iter().enumerate() {
-            let parts_ignore: Vec<&str> = part.split("don't()").collect();
-            if let Some(first_part) = parts_ignore.get(0) {
-                println!("Parts Ignore: {}", &first_part);
-                sum += p1(&first_part.to_string());
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+/// Scans the corrupted memory for well-formed instructions, skipping everything else.
+struct Instructions<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Instructions<'a> {
+    fn new(memory: &'a str) -> Self {
+        Self { rest: memory }
+    }
+}
+
+impl Iterator for Instructions<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.rest.find(['m', 'd'])?;
+            self.rest = &self.rest[start..];
+
+            if let Some(rest) = self.rest.strip_prefix("mul(") {
+                self.rest = rest;
+                if let Some((a, b, rest)) = mul_operands(rest) {
+                    self.rest = rest;
+                    return Some(Instruction::Mul(a, b));
+                }
+            } else if let Some(rest) = self.rest.strip_prefix("do()") {
+                self.rest = rest;
+                return Some(Instruction::Do);
+            } else if let Some(rest) = self.rest.strip_prefix("don't()") {
+                self.rest = rest;
+                return Some(Instruction::Dont);
+            } else {
+                self.rest = &self.rest[1..];
             }
         }
     }
-    Some(sum)
 }
 
-pub fn p1(line: &str) -> i32 {
-    let re = Regex::new(
-        r"(?x)
-            mul         # match literal
-            \(
-            (?<op1>\d{1,3}) # first operand
-            ,
-            (?<op2>\d{1,3}) # second operand
-            \)
-        ",
-    ).unwrap();
-    let sum = re
-        .captures_iter(line)
-        .map(|cap| cap["op1"].parse::<i32>().unwrap() * cap["op2"].parse::<i32>().unwrap())
-        .sum();
-    sum
+/// Parses the `X,Y)` following `mul(`, where both operands have one to three digits.
+fn mul_operands(text: &str) -> Option<(u32, u32, &str)> {
+    let (a, rest) = operand(text)?;
+    let rest = rest.strip_prefix(',')?;
+    let (b, rest) = operand(rest)?;
+    let rest = rest.strip_prefix(')')?;
+    Some((a, b, rest))
+}
+
+fn operand(text: &str) -> Option<(u32, &str)> {
+    let digits = text.bytes().take(3).take_while(u8::is_ascii_digit).count();
+    let value = text[..digits].parse().ok()?;
+    Some((value, &text[digits..]))
 }
 
 #[cfg(test)]
@@ -57,4 +100,11 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(48));
     }
+
+    #[test]
+    fn test_dont_spans_lines() {
+        let input = "mul(2,3)don't()mul(4,4)\nmul(5,5)do()mul(1234,1)mul(7,7)\nmul(1,1";
+        assert_eq!(part_one(input), Some(6 + 16 + 25 + 49));
+        assert_eq!(part_two(input), Some(6 + 49));
+    }
 }
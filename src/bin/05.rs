@@ -1,108 +1,77 @@
-use std::collections::{HashMap};
+use advent_of_code::order::DenseRules;
+use advent_of_code::parse::{self, ParseError};
 
 advent_of_code::solution!(5);
 
-pub fn part_one(input: &str) -> Option<u16> {
-    let manual: Manual = input.into();
-    let val: u16;
-
-    val = manual.sum_of_ordered();
-
-    Some(val)
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let manual = Manual::parse(input)?;
+    Ok(manual
+        .updates
+        .iter()
+        .filter(|update| manual.is_ordered(update))
+        .map(|update| middle(update))
+        .sum())
 }
 
-pub fn part_two(input: &str) -> Option<u16> {
-    let manual: Manual = input.into();
-    let val: u16;
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    let manual = Manual::parse(input)?;
+    Ok(manual
+        .updates
+        .iter()
+        .filter(|update| !manual.is_ordered(update))
+        .map(|update| {
+            let mut update = update.clone();
+            update.sort_by(manual.rules.comparator());
+            middle(&update)
+        })
+        .sum())
+}
 
-    val = manual.sum_of_unordered();
-    Some(val)
+fn middle(update: &[u16]) -> u32 {
+    u32::from(update[update.len() / 2])
 }
 
 struct Manual {
-    rules: HashMap<u16, Vec<u16>>,
+    rules: DenseRules<u16>,
     updates: Vec<Vec<u16>>,
 }
 
 impl Manual {
-    fn sum_of_ordered(&self) -> u16 {
-        let mut val = 0;
-        self.updates.iter()
-            .filter(|&update| self.is_correct_order(&update))
-            .for_each(|update| {
-                val += update[update.len() / 2];
-            });
-        val
-    }
-
-    fn sum_of_unordered(&self) -> u16 {
-        let mut val = 0;
-        self.updates.iter()
-            .filter(|&update| !self.is_correct_order(&update))
-            .for_each(|update| {
-                let corrected_order = self.fix_order(&update);
-                val += corrected_order[corrected_order.len() / 2];
-            });
-        val
-    }
-
-    fn is_correct_order(&self, update: &Vec<u16>) -> bool {
-        let mut processed = Vec::<u16>::new();
-        for num in update {
-            for page in &processed {
-                if let Some(rule) = &self.rules.get(page) {
-                    if rule.contains(num) {
-                        return false;
-                    }
-                }
-            }
-            processed.push(*num);
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut blocks = parse::numbered_blocks(input);
+        let (first_rule, rules) = blocks.next().unwrap_or((1, ""));
+        let Some((first_update, updates)) = blocks.next() else {
+            // without the blank line, the updates are read as more rules.
+            let line = rules
+                .lines()
+                .position(|line| line.contains(','))
+                .unwrap_or(rules.lines().count());
+            return Err(ParseError::new(
+                first_rule + line,
+                "expected a blank line between the ordering rules and the updates",
+            ));
+        };
+        if let Some((line, _)) = blocks.next() {
+            return Err(ParseError::new(
+                line,
+                "expected the updates to be the last section",
+            ));
         }
-        true
-    }
 
-    fn fix_order(&self, update: &Vec<u16>) -> Vec<u16> {
-        let mut processed: Vec<u16> = Vec::new();
-        for (i, num) in update.iter().enumerate() {
-            let mut index_to_insert = i;
-            for (index, page) in processed.iter().enumerate() {
-                if let Some(rule) = &self.rules.get(page) {
-                    if rule.contains(num) {
-                        index_to_insert = index;
-                        break;
-                    }
-                }
-            }
-            processed.insert(index_to_insert, *num);
-        }
-        processed
+        let rules: Vec<(u16, u16)> =
+            parse::lines_from(first_rule, rules, |line| parse::pair(line, "|"))?;
+        let updates = parse::lines_from(first_update, updates, |line| parse::list(line, ","))?;
+        Ok(Self {
+            rules: rules.into_iter().collect(),
+            updates,
+        })
     }
-}
-
-impl From<&str> for Manual {
-    fn from(input: &str) -> Self {
-        let manual: Vec<&str> = input.split("\n\n").collect(); // this splits the top part and the bottom part of the inputs.
-        let (rules_part, updates_part) = (manual[0], manual[1]);
 
-        let mut rules: HashMap<u16, Vec<u16>> = HashMap::new();
-        for line in rules_part.lines() {
-            let parts: Vec<u16> = line.split('|')
-                .filter_map(|p| p.trim().parse().ok())
-                .collect();
-            rules.entry(parts[1]).or_insert(vec![]).push(parts[0]);
-        }
-
-        let mut updates: Vec<Vec<u16>> = Vec::new();
-        for line in updates_part.lines() {
-            let update: Vec<u16> = line.split(',')
-                .filter_map(|p| p.trim().parse().ok())
-                .collect();
-            if !update.is_empty() {
-                updates.push(update);
-            }
-        }
-
-        Manual { rules, updates }
+    /// The rules order every pair of pages within an update, so checking neighbours is enough.
+    fn is_ordered(&self, update: &[u16]) -> bool {
+        update
+            .windows(2)
+            .all(|pair| !self.rules.is_before(pair[1], pair[0]))
     }
 }
 
@@ -113,12 +82,30 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
+        assert_eq!(result, Ok(143));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
+        assert_eq!(result, Ok(123));
+    }
+
+    #[test]
+    fn test_missing_separator() {
+        let error = part_one("47|53\n97|13\n75,47,61,53,29\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: expected a blank line between the ordering rules and the updates"
+        );
+        let error = part_one("47|53\n97|13\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3: expected a blank line between the ordering rules and the updates"
+        );
+        let error = part_two("47|53\n\n75,x,61\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 3: could not parse \"x\"");
+        let error = part_one("\n\n47|53\n97-13\n\n75,47\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 4: expected \"|\" in \"97-13\"");
     }
 }
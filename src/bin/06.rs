@@ -1,201 +1,181 @@
-use std::{ops::Add, collections::HashSet};
+use advent_of_code::bitset::{BitGrid, DirectedBitGrid};
+use advent_of_code::visualize::{self, Color, Frame};
+use advent_of_code::{Direction, Grid};
 
 advent_of_code::solution!(6);
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut lab = Lab::from(input);
-    let count = lab.walk().visited.len() as u32;
-    Some(count)
+    let lab = Lab::parse(input)?;
+    let mut visited = BitGrid::for_grid(&lab.grid);
+    lab.patrol(|position, direction| {
+        visited.insert(position);
+        visualize::emit(|| {
+            Frame::new(&lab.grid)
+                .path(visited.iter(), Color::Green)
+                .marker(position, arrow(direction), Color::Red)
+                .caption(format!("{} positions visited", visited.count()))
+        });
+    });
+    Some(visited.count() as u32)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut lab = Lab::from(input);
-    let count = lab.sabatoge().diversions.len() as u32;
-    Some(count)
+    let lab = Lab::parse(input)?;
+    // an obstacle can only go where the guard has not been yet, or they would have bumped into it earlier.
+    let mut visited = BitGrid::for_grid(&lab.grid);
+    visited.insert(lab.guard);
+    let mut turns = DirectedBitGrid::for_grid(&lab.grid);
+    let mut trail = vec![];
+    let mut obstacles = vec![];
+    lab.patrol(|position, direction| {
+        let Some(ahead) = lab.grid.offset(position, direction.grid_offset()) else {
+            return;
+        };
+        if lab.grid[ahead] == b'#' || !visited.insert(ahead) {
+            return;
+        }
+        let looped = lab.loops_with(ahead, position, direction, &mut turns, &mut trail);
+        if looped {
+            obstacles.push(ahead);
+        }
+        visualize::emit(|| {
+            let color = if looped { Color::Magenta } else { Color::Gray };
+            Frame::new(&lab.grid)
+                .highlight(obstacles.iter().copied(), Color::Magenta)
+                .marker(ahead, 'O', color)
+                .caption(format!("{} obstacles make the guard loop", obstacles.len()))
+        });
+    });
+    Some(obstacles.len() as u32)
 }
 
 struct Lab {
-    grid: Grid,
-    guard: Guard,
-    visited: HashSet<Position>,
-    diversions: HashSet<Position>,
-    og: Guard,
+    grid: Grid<u8>,
+    guard: (usize, usize),
+    /// For every direction in [`Direction::ORTHOGONAL`] order, the position where a guard walking from a cell
+    /// stops in front of an obstacle, or `None` if they leave the lab.
+    jumps: [Grid<Option<(usize, usize)>>; 4],
 }
 
 impl Lab {
-    fn walk(&mut self) -> &Self {
-        loop {
-            let next = self.guard.position + self.guard.position.direction.offset();
-
-            // This takes a lot longer than using contains on the HashSet that doesn't track position direction,
-            // but we need to store unique row, col values for Part 1
-            // Part 2 requires us to also know the direction of the position, however it breaks if we treat the same x, y as different
-            // depending on the way the guard is facing.
-            let v = self.visited.iter().any(|pos| pos.row == self.guard.position.row && pos.col == self.guard.position.col);
-            if !v {
-                self.visited.insert(self.guard.position);
-            }
-
-            match self.grid.get(next) {
-                Some(b'#') => self.guard.position.direction = self.guard.position.direction.turn(),
-                Some(_) => self.guard.position = next,
-                None => break,
-            }
+    fn parse(input: &str) -> Option<Self> {
+        let grid = advent_of_code::parse::grid(input).ok()?;
+        let guard = grid.position(|&cell| cell == b'^')?;
+        let jumps = Direction::ORTHOGONAL.map(|direction| Self::jump_table(&grid, direction));
+        Some(Self { grid, guard, jumps })
+    }
+
+    fn jump_table(grid: &Grid<u8>, direction: Direction) -> Grid<Option<(usize, usize)>> {
+        let mut jumps = Grid::new(grid.width(), grid.height(), None);
+        // the cell ahead has to be known first, so walk against the direction.
+        let mut positions: Vec<_> = grid.positions().collect();
+        if matches!(direction, Direction::Down | Direction::Right) {
+            positions.reverse();
+        }
+        for position in positions {
+            jumps[position] = match grid.offset(position, direction.grid_offset()) {
+                None => None,
+                Some(ahead) if grid[ahead] == b'#' => Some(position),
+                Some(ahead) => jumps[ahead],
+            };
         }
-        self
+        jumps
     }
 
-    fn sabatoge(&mut self) -> &Self {
-        // Get the conditions right.
-        self.walk();
-        for (i, pos) in self.visited.iter().enumerate() {
-            // reset guard to starting point
-            let guard = self.og.clone();
-            let mut grid = self.grid.clone();
-            // change grid to add obstacle
-            grid.set(*pos, b'#');
-            if self.check_diversion(grid, guard) {
-                self.diversions.insert(*pos);
-            }
-        }
-        self
+    fn jump(&self, position: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        self.jumps[direction.index() / 2][position]
     }
 
-    fn check_diversion(&self, grid: Grid, mut guard: Guard) -> bool {
-        let mut visited: HashSet<Position> = HashSet::new();
-        let mut i = 0;
+    /// Walks the guard's route step by step, calling `step` with every position and the direction the guard
+    /// faces when moving on from it, up to the position where they leave the lab.
+    fn patrol(&self, mut step: impl FnMut((usize, usize), Direction)) {
+        let (mut position, mut direction) = (self.guard, Direction::Up);
         loop {
-            let next = guard.position + guard.position.direction.offset();
-            if visited.contains(&guard.position) {
-                return true;
-            }
-            visited.insert(guard.position);
-            match grid.get(next) {
-                Some(b'#') => guard.position.direction = guard.position.direction.turn(),
-                Some(_) => guard.position = next,
-                None => return false
+            match self.grid.offset(position, direction.grid_offset()) {
+                Some(ahead) if self.grid[ahead] == b'#' => direction = direction.turn_right(),
+                Some(ahead) => {
+                    step(position, direction);
+                    position = ahead;
+                }
+                None => {
+                    step(position, direction);
+                    return;
+                }
             }
-            i += 1;
         }
     }
-}
-
-impl From<&str> for Lab {
-    fn from(input: &str) -> Self {
-        let grid = Grid::from(input);
-        let guard = Guard {
-            position: grid.find_character(b'^', Direction::Up),
-        };
-        let og = guard.clone();
-        // Self { grid, guard, routes }
-        Self { grid, guard, visited: HashSet::new(), diversions: HashSet::new(), og }
-    }
-}
 
-#[derive(Clone, Debug)]
-struct Grid {
-    bytes: Vec<Vec<u8>>,
-}
-
-impl Grid {
-    fn get(&self, position: Position) -> Option<u8> {
-        self.bytes
-            .get(position.row as usize)?.get(position.col as usize).copied()
-    }
-
-    fn set(&mut self, position: Position, byte: u8) -> Self {
-        if let Some(row) = self.bytes.get_mut(position.row as usize) {
-            if let Some(cell) = row.get_mut(position.col as usize) {
-                *cell = byte;
+    /// Whether an extra obstacle makes the guard, who is about to walk into it, go round in circles.
+    ///
+    /// The guard jumps from obstacle to obstacle, and loops once they stop at the same place facing the same
+    /// way twice. `turns` is left empty, `trail` only lends its allocation.
+    fn loops_with(
+        &self,
+        obstacle: (usize, usize),
+        mut position: (usize, usize),
+        mut direction: Direction,
+        turns: &mut DirectedBitGrid,
+        trail: &mut Vec<((usize, usize), Direction)>,
+    ) -> bool {
+        let looped = loop {
+            let jump = self.jump(position, direction);
+            let stop = match distance(position, obstacle, direction) {
+                Some(steps)
+                    if jump.is_none_or(|stop| {
+                        steps <= distance(position, stop, direction).unwrap_or(0)
+                    }) =>
+                {
+                    Some(walk(position, direction, steps - 1))
+                }
+                _ => jump,
+            };
+            let Some(stop) = stop else {
+                break false;
+            };
+            if !turns.insert(stop, direction) {
+                break true;
             }
+            trail.push((stop, direction));
+            position = stop;
+            direction = direction.turn_right();
+        };
+        for (position, direction) in trail.drain(..) {
+            turns.remove(position, direction);
         }
-        self.clone()
-    }
-
-    fn find_character(&self, target: u8, direction: Direction) -> Position {
-        self.bytes.iter().enumerate().find_map(|(row_index, row)| {
-            row.iter()
-                .position(|&byte| byte == target)
-                .map(|col_index| Position::new(col_index as i32, row_index as i32, direction))
-            })
-            .unwrap_or_default()
-    }
-
-    fn show(&self) {
-        for row in &self.bytes {
-            let line: String = row.iter()
-                .map(|&byte| char::from(byte))
-                .collect();
-            println!("{}", line);
-        }
-    }
-}
-
-impl From<&str> for Grid {
-    fn from(input: &str) -> Self {
-        let bytes: Vec<Vec<u8>> = input.lines().map(|row| row.bytes().collect()).collect();
-        Self { bytes }
-    }
-}
-
-#[derive(Clone)]
-struct Guard {
-    position: Position,
-}
-
-#[derive(Copy, Clone, Eq, Hash, PartialEq, Debug)]
-enum Direction {
-    Up, Down, Right, Left
-}
-
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Up
+        looped
     }
 }
 
-impl Direction {
-    fn offset(self) -> Offset {
-        match self {
-            Direction::Up => Offset(0, -1),
-            Direction::Down => Offset(0, 1),
-            Direction::Left => Offset(-1, 0),
-            Direction::Right => Offset(1, 0),
-        }
+/// The symbol of a guard facing `direction`.
+fn arrow(direction: Direction) -> char {
+    match direction {
+        Direction::Right => '>',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+        _ => '^',
     }
-
-    fn turn(self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
-}
-
-#[derive(Default, Copy, Clone, Eq, Hash, PartialEq, Debug)]
-struct Position{
-    row: i32,
-    col: i32,
-    direction: Direction,
 }
 
-impl Add<Offset> for Position {
-    type Output = Self;
-    fn add(self, Offset(dx, dy): Offset) -> Self::Output {
-        Position::new(self.col + dx, self.row + dy, self.direction)
+/// The number of steps from `from` to `to` when walking in `direction`, if `to` lies ahead.
+fn distance(from: (usize, usize), to: (usize, usize), direction: Direction) -> Option<usize> {
+    let (d_row, d_col) = direction.grid_offset();
+    let rows = to.0 as isize - from.0 as isize;
+    let cols = to.1 as isize - from.1 as isize;
+    match (d_row, d_col) {
+        (0, _) if rows == 0 && cols * d_col > 0 => Some(cols.unsigned_abs()),
+        (_, 0) if cols == 0 && rows * d_row > 0 => Some(rows.unsigned_abs()),
+        _ => None,
     }
 }
 
-impl Position {
-    fn new(col: i32, row: i32, direction: Direction) -> Self {
-        Position { col, row, direction }
-    }
+fn walk(position: (usize, usize), direction: Direction, steps: usize) -> (usize, usize) {
+    let (d_row, d_col) = direction.grid_offset();
+    (
+        position.0.wrapping_add_signed(d_row * steps as isize),
+        position.1.wrapping_add_signed(d_col * steps as isize),
+    )
 }
 
-struct Offset(i32, i32);
-
 #[cfg(test)]
 mod tests {
     use super::*;
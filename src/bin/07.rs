@@ -1,132 +1,54 @@
-advent_of_code::solution!(7);
+use std::str::FromStr;
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let mut sum: u64 = 0;
+use advent_of_code::equation::{Operator, Solver};
+use advent_of_code::parse::{self, ParseError};
 
-    input.lines()
-        .for_each(|line| {
-            let mut parts = line.split(":");
-            let left = parts.next().unwrap().trim().parse::<u64>().unwrap();
-            let right = parts.next().unwrap().trim().split_whitespace()
-                .map(|s| s.parse::<u64>().unwrap())
-                .collect::<Vec<u64>>();
-            sum += eval_line_p1(left, right);
-        });
+advent_of_code::solution!(7);
 
-    Some(sum)
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let solver = Solver::new([Operator::ADD, Operator::MUL]);
+    sum_of_solvable(input, &solver)
 }
 
-/*
-OK, so let's dig into this solution.
-if we have 2 numbers, we have 2 potential operations
-if we have 3 numbers, we have A + B + C, A + B * C, A * B * C, A * B + C for 4 operations
-for 4 numbers:
-A + B + C + D
-A + B + C * D
-A + B * C + D
-A * B + C + D
-A + B * C * D
-A * B + C * D
-A * B * C + D
-A * B * C * D
-
-I need to parse lines with the following structure of numbers `123: 4 6 8` where I need to store the left side value and create a vector with the right numbers that are separated by whitespace.
-
-A = 2, B = 4, C = 5, D = 7
-
-A + B, A * B => 6, 8
-Res + C, Res * C => 11, 30, 13, 40
-Res + D, Res * D => 18, 77, 37, 210, 20, 91, 47, 280
-
-for 5 numbers:
-A + B + C + D + E
-A + B + C * D + E
-A + B * C + D + E
-A * B + C + D + E
-A + B * C * D + E
-A * B + C * D + E
-A * B * C + D + E
-A * B * C * D + E
-A + B + C + D * E
-A + B + C * D * E
-A + B * C + D * E
-A * B + C + D * E
-A + B * C * D * E
-A * B + C * D * E
-A * B * C + D * E
-A * B * C * D * E
-
-number of operation possibilities is (n-1)^2
-*/
-
-pub fn part_two(input: &str) -> Option<u64> {
-    let mut sum: u64 = 0;
-
-    input.lines()
-        .for_each(|line| {
-            let mut parts = line.split(":");
-            let left = parts.next().unwrap().trim().parse::<u64>().unwrap();
-            let right = parts.next().unwrap().trim().split_whitespace()
-                .map(|s| s.parse::<u64>().unwrap())
-                .collect::<Vec<u64>>();
-            sum += eval_line_p2(left, right);
-        });
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let solver = Solver::new([Operator::ADD, Operator::MUL, Operator::CONCAT]);
+    sum_of_solvable(input, &solver)
+}
 
-    Some(sum)
+/// Every operator can be undone, so the solver works back from the target and drops a branch as soon as the
+/// target is smaller than the last operand, not divisible by it or does not end in its digits.
+fn sum_of_solvable(input: &str, solver: &Solver<u64>) -> Result<u64, ParseError> {
+    let equations = parse::lines(input, Equation::from_str)?;
+    Ok(equations
+        .iter()
+        .filter(|equation| solver.is_solvable(equation.target, &equation.operands))
+        .map(|equation| equation.target)
+        .sum())
 }
 
-pub fn eval_line_p1(total: u64, nums: Vec<u64>) -> u64 {
-    let mut results: Vec<Vec<u64>> = Vec::new();
-    let mut sum: u64 = 0;
-    // set up the conditions to be building the evaluations.
-    results.push(vec![nums[0]]);
-    for &num in &nums[1..] {
-        let mut vals: Vec<u64> = Vec::new();
-        if let Some(last_vec) = results.last() {
-            for &r in last_vec.iter() {
-                vals.push(r + num);
-                vals.push(r * num);
-            }
-        }
-        results.push(vals);
-        // println!("Total: {} => Results Vec: {:?}", total, results);
-    }
-    if let Some(last_vec) = results.last() {
-        if last_vec.contains(&total) {
-            sum = total;
-        }
-    }
-    sum
+/// A line like `190: 10 19`.
+struct Equation {
+    target: u64,
+    operands: Vec<u64>,
 }
 
-pub fn eval_line_p2(total: u64, nums: Vec<u64>) -> u64 {
-    let mut results: Vec<Vec<u64>> = Vec::new();
-    let mut sum: u64 = 0;
-    // set up the conditions to be building the evaluations.
-    results.push(vec![nums[0]]);
-    for &num in &nums[1..] {
-        let mut vals: Vec<u64> = Vec::new();
-        if let Some(last_vec) = results.last() {
-            for &r in last_vec.iter() {
-                vals.push(r + num);
-                vals.push(r * num);
-                vals.push(concatenate(r, num));
-            }
-        }
-        results.push(vals);
-        // println!("Total: {} => Results Vec: {:?}", total, results);
-    }
-    if let Some(last_vec) = results.last() {
-        if last_vec.contains(&total) {
-            sum = total;
+impl FromStr for Equation {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (target, operands) = line
+            .split_once(':')
+            .ok_or_else(|| format!("expected \":\" in \"{line}\""))?;
+        let target = target
+            .trim()
+            .parse()
+            .map_err(|_| format!("could not parse \"{}\"", target.trim()))?;
+        let operands: Vec<u64> = parse::list(operands, "")?;
+        if operands.is_empty() {
+            return Err(format!("expected operands after \":\" in \"{line}\""));
         }
+        Ok(Self { target, operands })
     }
-    sum
-}
-
-pub fn concatenate(a: u64, b: u64) -> u64 {
-    let concatenated = format!("{}{}", a, b);
-    concatenated.parse::<u64>().unwrap()
 }
 
 #[cfg(test)]
@@ -136,12 +58,20 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3749));
+        assert_eq!(result, Ok(3749));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11387));
+        assert_eq!(result, Ok(11387));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = part_one("190: 10 19\n3267 81 40 27").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected \":\" in \"3267 81 40 27\"");
+        let error = part_two("83: 17 x").unwrap_err();
+        assert_eq!(error.to_string(), "line 1: could not parse \"x\"");
     }
 }
@@ -1,125 +1,93 @@
-use std::{collections::{HashMap, HashSet}, ops::{Add, Sub, AddAssign}};
+use std::collections::HashMap;
+
+use advent_of_code::bitset::BitGrid;
+use advent_of_code::math::gcd;
+use advent_of_code::Point;
 
 advent_of_code::solution!(8);
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let map: Map = Map::from(input);
-    map.signal().len().try_into().ok()
+    let map = Map::from(input);
+    let mut antinodes = BitGrid::new(map.width, map.height);
+    for (a, b) in map.pairs() {
+        let offset = b - a;
+        for antinode in [a - offset, b + offset] {
+            if let Some(position) = map.position(antinode) {
+                antinodes.insert(position);
+            }
+        }
+    }
+    antinodes.count().try_into().ok()
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let map: Map = Map::from(input);
-    map.harmonics().len().try_into().ok()
+    let map = Map::from(input);
+    let mut antinodes = BitGrid::new(map.width, map.height);
+    for (a, b) in map.pairs() {
+        // every grid point on the line counts, not only multiples of the distance between the antennas.
+        let offset = b - a;
+        let step = offset / gcd(offset.x.unsigned_abs(), offset.y.unsigned_abs()) as i64;
+        for step in [step, -step] {
+            let mut point = a;
+            while let Some(position) = map.position(point) {
+                antinodes.insert(position);
+                point += step;
+            }
+        }
+    }
+    antinodes.count().try_into().ok()
 }
 
+/// Antenna positions by frequency. Ragged inputs span the longest line, shorter lines are treated as if
+/// they were padded with `.`.
 #[derive(Debug)]
 struct Map {
-    lists: HashMap<u8, Vec<Point>>,
-    rows: usize,
-    cols: usize
+    antennas: HashMap<u8, Vec<Point<i64>>>,
+    width: usize,
+    height: usize,
 }
 
 impl Map {
-    // we don't actually care about the number of antinodes per antenna, just total amount
-    // so we need to build the pairs list which relates the same antenna type to each other
-    // but adds to the total list that we can work through.
-    fn get_pairs(&self) -> Vec<(Point, Point)> {
-        self.lists.values()
-           .flat_map(|antenna| {
-                antenna.iter()
-                    .flat_map(|&p1| antenna.iter().map(move |&p2| (p1, p2)))
-                    .filter(|(p1, p2)| p1 != p2)
-                })
-                .collect()
-    }
-
-    fn signal(&self) -> HashSet<Point> {
-        let mut antinodes = HashSet::new();
-
-        for (p1, p2) in self.get_pairs() {
-            [p1 + (p1 - p2), p2 + (p2 - p1)]
-                .into_iter()
-                .filter(move |&antinode| self.is_valid_antinode(antinode))
-                .for_each(|antinode| {
-                    antinodes.insert(antinode);
-                })
-        }
-        antinodes
-    }
-
-    fn harmonics(&self) -> HashSet<Point> {
-        let mut harmonics = HashSet::new();
-        for (p1, p2) in self.get_pairs() {
-            harmonics.insert(p1);
-            harmonics.insert(p2);
-            harmonics.extend(self.check_harmonics(p1, p1 - p2));
-            harmonics.extend(self.check_harmonics(p2, p2 - p1));
-        }
-        harmonics
-    }
-
-    fn check_harmonics(&self, p: Point, dp: Point) -> HashSet<Point> {
-        let mut harmonics: HashSet<Point> = HashSet::new();
-        let np = p + dp;
-        if self.is_valid_antinode(np) {
-            harmonics.insert(np);
-            harmonics.extend(self.check_harmonics(np, dp));
-        }
-        harmonics
+    /// Every unordered pair of antennas with the same frequency.
+    fn pairs(&self) -> impl Iterator<Item = (Point<i64>, Point<i64>)> + '_ {
+        self.antennas.values().flat_map(|antennas| {
+            antennas
+                .iter()
+                .enumerate()
+                .flat_map(move |(i, &a)| antennas[i + 1..].iter().map(move |&b| (a, b)))
+        })
     }
 
-    fn is_valid_antinode(&self, node: Point) -> bool {
-        node.0 >= 0 && node.0 <= self.cols as i32 && node.1 >= 0 && node.1 <= self.rows as i32
+    /// The `(row, col)` position of a point, if it lies on the map.
+    fn position(&self, point: Point<i64>) -> Option<(usize, usize)> {
+        point
+            .to_grid()
+            .filter(|&(row, col)| row < self.height && col < self.width)
     }
 }
 
 impl From<&str> for Map {
     fn from(input: &str) -> Self {
-        let mut lists: HashMap<u8, Vec<Point>> = HashMap::new();
-        let ignored_chars: HashSet<u8> = vec![b'.'].into_iter().collect();
-        let (mut rows, mut cols) = (0, 0);
-
-        for (y, line) in input.lines().enumerate() {
-            for (x, byte) in line.bytes().enumerate() {
-                if !ignored_chars.contains(&byte) {
-                    lists.entry(byte).or_insert_with(Vec::new).push(Point(x as i32, y as i32));
+        let mut antennas: HashMap<u8, Vec<Point<i64>>> = HashMap::new();
+        let (mut width, mut height) = (0, 0);
+
+        for (row, line) in input.lines().enumerate() {
+            for (col, byte) in line.bytes().enumerate() {
+                if byte != b'.' {
+                    antennas
+                        .entry(byte)
+                        .or_default()
+                        .push(Point::new(col as i64, row as i64));
                 }
-                cols = x;
             }
-            rows = y;
+            width = width.max(line.len());
+            height = row + 1;
+        }
+        Self {
+            antennas,
+            width,
+            height,
         }
-        Self { lists, rows, cols }
-    }
-}
-
-#[derive(Clone, Copy, Default, Hash, Eq, Debug)]
-struct Point(i32, i32);
-
-impl PartialEq for Point {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0 && self.1 == other.1
-    }
-}
-
-impl Add for Point {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        Self(self.0 + other.0, self.1 + other.1)
-    }
-}
-
-impl Sub for Point {
-    type Output = Self;
-
-    fn sub(self, other: Self) -> Self {
-        Self(self.0 - other.0, self.1 - other.1)
-    }
-}
-
-impl AddAssign for Point {
-    fn add_assign(&mut self, other: Self) {
-        *self = Self(self.0 + other.0, self.1 + other.1)
     }
 }
 
@@ -138,4 +106,23 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(34));
     }
+
+    #[test]
+    fn test_non_square() {
+        // the antennas are 4 apart, so every cell of the row is on their line.
+        let input = "A...A....";
+        assert_eq!(part_one(input), Some(1));
+        assert_eq!(part_two(input), Some(9));
+
+        let input = "..........\n...a......\n.....a....";
+        assert_eq!(part_one(input), Some(1));
+        assert_eq!(part_two(input), Some(3));
+    }
+
+    #[test]
+    fn test_ragged() {
+        let input = "a\n.a\n..\n......";
+        assert_eq!(part_one(input), Some(1));
+        assert_eq!(part_two(input), Some(4));
+    }
 }
@@ -0,0 +1,556 @@
+//! Dense sets backed by bits, for tracking grid positions without hashing.
+//!
+//! [`BitGrid`] stores `(row, col)` positions like [`Grid`], [`DirectedBitGrid`] additionally stores a
+//! [`Direction`] per position, e.g. to detect a guard walking the same way through a cell twice.
+use std::fmt::{self, Display};
+
+use crate::{grid::Grid, point::Direction};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A set of indices in `0..capacity`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+}
+
+impl BitSet {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(BITS)],
+            capacity,
+        }
+    }
+
+    /// The exclusive upper bound of the indices this set can hold.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds an index, returning whether it was newly inserted.
+    ///
+    /// # Panics
+    /// Panics if `index` is not below the capacity.
+    pub fn insert(&mut self, index: usize) -> bool {
+        assert!(
+            index < self.capacity,
+            "index {index} is outside of the set's capacity {}",
+            self.capacity
+        );
+        let (word, mask) = (index / BITS, 1 << (index % BITS));
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    /// Removes an index, returning whether it was present.
+    pub fn remove(&mut self, index: usize) -> bool {
+        if index >= self.capacity {
+            return false;
+        }
+        let (word, mask) = (index / BITS, 1 << (index % BITS));
+        let removed = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        removed
+    }
+
+    #[must_use]
+    pub fn contains(&self, index: usize) -> bool {
+        index < self.capacity && self.words[index / BITS] & (1 << (index % BITS)) != 0
+    }
+
+    /// The number of indices in the set.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Iterates over the indices in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * BITS + bit)
+            })
+        })
+    }
+
+    fn assert_same_capacity(&self, other: &Self) {
+        assert_eq!(
+            self.capacity, other.capacity,
+            "sets must have the same capacity"
+        );
+    }
+
+    fn combine(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        self.assert_same_capacity(other);
+        for (word, &other) in self.words.iter_mut().zip(&other.words) {
+            *word = f(*word, other);
+        }
+    }
+
+    /// Adds every index of `other`. Both sets must have the same capacity.
+    pub fn union_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a | b);
+    }
+
+    /// Keeps only the indices also in `other`. Both sets must have the same capacity.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a & b);
+    }
+
+    /// Removes every index of `other`. Both sets must have the same capacity.
+    pub fn difference_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a & !b);
+    }
+
+    /// Keeps the indices in exactly one of the sets. Both sets must have the same capacity.
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a ^ b);
+    }
+
+    /// Whether every index is also in `other`.
+    ///
+    /// # Panics
+    /// Panics if the sets have different capacities.
+    #[must_use]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.assert_same_capacity(other);
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(&a, &b)| a & !b == 0)
+    }
+
+    /// Whether no index is also in `other`.
+    ///
+    /// # Panics
+    /// Panics if the sets have different capacities.
+    #[must_use]
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.assert_same_capacity(other);
+        self.words
+            .iter()
+            .zip(&other.words)
+            .all(|(&a, &b)| a & b == 0)
+    }
+}
+
+/// A set of `(row, col)` positions in a `width` x `height` grid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    bits: BitSet,
+    width: usize,
+    height: usize,
+}
+
+impl BitGrid {
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bits: BitSet::new(width * height),
+            width,
+            height,
+        }
+    }
+
+    /// An empty set with the same dimensions as `grid`.
+    #[must_use]
+    pub fn for_grid<T>(grid: &Grid<T>) -> Self {
+        Self::new(grid.width(), grid.height())
+    }
+
+    /// The positions of all cells of `grid` matching `predicate`.
+    #[must_use]
+    pub fn from_grid<T>(grid: &Grid<T>, predicate: impl Fn(&T) -> bool) -> Self {
+        let mut set = Self::for_grid(grid);
+        for (position, cell) in grid.iter() {
+            if predicate(cell) {
+                set.insert(position);
+            }
+        }
+        set
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, (row, col): (usize, usize)) -> Option<usize> {
+        (row < self.height && col < self.width).then_some(row * self.width + col)
+    }
+
+    fn checked_index(&self, (row, col): (usize, usize)) -> usize {
+        self.index_of((row, col)).unwrap_or_else(|| {
+            panic!(
+                "position ({row}, {col}) is outside of the {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+
+    /// Adds a position, returning whether it was newly inserted.
+    ///
+    /// # Panics
+    /// Panics if the position is outside the grid.
+    pub fn insert(&mut self, position: (usize, usize)) -> bool {
+        let index = self.checked_index(position);
+        self.bits.insert(index)
+    }
+
+    /// Removes a position, returning whether it was present.
+    pub fn remove(&mut self, position: (usize, usize)) -> bool {
+        self.index_of(position)
+            .is_some_and(|index| self.bits.remove(index))
+    }
+
+    /// Whether the position is in the set. Positions outside the grid never are.
+    #[must_use]
+    pub fn contains(&self, position: (usize, usize)) -> bool {
+        self.index_of(position)
+            .is_some_and(|index| self.bits.contains(index))
+    }
+
+    /// Like [`BitGrid::contains`], for positions that may be negative.
+    #[must_use]
+    pub fn contains_signed(&self, row: isize, col: isize) -> bool {
+        match (usize::try_from(row), usize::try_from(col)) {
+            (Ok(row), Ok(col)) => self.contains((row, col)),
+            _ => false,
+        }
+    }
+
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Iterates over the positions in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.bits
+            .iter()
+            .map(|index| (index / self.width, index % self.width))
+    }
+
+    /// Adds every position of `other`. Both grids must have the same dimensions.
+    pub fn union_with(&mut self, other: &Self) {
+        self.assert_same_size(other);
+        self.bits.union_with(&other.bits);
+    }
+
+    /// Keeps only the positions also in `other`. Both grids must have the same dimensions.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.assert_same_size(other);
+        self.bits.intersect_with(&other.bits);
+    }
+
+    /// Removes every position of `other`. Both grids must have the same dimensions.
+    pub fn difference_with(&mut self, other: &Self) {
+        self.assert_same_size(other);
+        self.bits.difference_with(&other.bits);
+    }
+
+    #[must_use]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.assert_same_size(other);
+        self.bits.is_subset(&other.bits)
+    }
+
+    #[must_use]
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.assert_same_size(other);
+        self.bits.is_disjoint(&other.bits)
+    }
+
+    fn assert_same_size(&self, other: &Self) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "grids must have the same dimensions, found {}x{} and {}x{}",
+            self.width,
+            self.height,
+            other.width,
+            other.height
+        );
+    }
+
+    #[must_use]
+    pub fn to_grid(&self) -> Grid<bool> {
+        let mut grid = Grid::new(self.width, self.height, false);
+        for position in self.iter() {
+            grid[position] = true;
+        }
+        grid
+    }
+}
+
+/// Displays the set as `#` for contained and `.` for other positions.
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_grid().fmt(f)
+    }
+}
+
+/// A set of `((row, col), direction)` states in a `width` x `height` grid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DirectedBitGrid {
+    bits: BitSet,
+    width: usize,
+    height: usize,
+}
+
+impl DirectedBitGrid {
+    const DIRECTIONS: usize = Direction::ALL.len();
+
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            bits: BitSet::new(width * height * Self::DIRECTIONS),
+            width,
+            height,
+        }
+    }
+
+    /// An empty set with the same dimensions as `grid`.
+    #[must_use]
+    pub fn for_grid<T>(grid: &Grid<T>) -> Self {
+        Self::new(grid.width(), grid.height())
+    }
+
+    fn index_of(&self, (row, col): (usize, usize), direction: Direction) -> Option<usize> {
+        (row < self.height && col < self.width)
+            .then_some((row * self.width + col) * Self::DIRECTIONS + direction.index())
+    }
+
+    /// Adds a state, returning whether it was newly inserted.
+    ///
+    /// # Panics
+    /// Panics if the position is outside the grid.
+    pub fn insert(&mut self, position: (usize, usize), direction: Direction) -> bool {
+        let Some(index) = self.index_of(position, direction) else {
+            panic!(
+                "position {position:?} is outside of the {}x{} grid",
+                self.width, self.height
+            );
+        };
+        self.bits.insert(index)
+    }
+
+    /// Removes a state, returning whether it was present.
+    pub fn remove(&mut self, position: (usize, usize), direction: Direction) -> bool {
+        self.index_of(position, direction)
+            .is_some_and(|index| self.bits.remove(index))
+    }
+
+    #[must_use]
+    pub fn contains(&self, position: (usize, usize), direction: Direction) -> bool {
+        self.index_of(position, direction)
+            .is_some_and(|index| self.bits.contains(index))
+    }
+
+    /// The number of states, counting a position once per direction.
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.bits.count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), Direction)> + '_ {
+        self.bits.iter().map(|index| {
+            let cell = index / Self::DIRECTIONS;
+            (
+                (cell / self.width, cell % self.width),
+                Direction::ALL[index % Self::DIRECTIONS],
+            )
+        })
+    }
+
+    /// The positions reached in any direction.
+    #[must_use]
+    pub fn positions(&self) -> BitGrid {
+        let mut positions = BitGrid::new(self.width, self.height);
+        for (position, _) in self.iter() {
+            positions.insert(position);
+        }
+        positions
+    }
+
+    /// Adds every state of `other`. Both grids must have the same dimensions.
+    pub fn union_with(&mut self, other: &Self) {
+        assert!(
+            self.width == other.width && self.height == other.height,
+            "grids must have the same dimensions"
+        );
+        self.bits.union_with(&other.bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::*;
+
+    #[test]
+    fn bit_set_matches_hash_set() {
+        let mut bits = BitSet::new(200);
+        let mut expected = BTreeSet::new();
+        for i in 0..1000_usize {
+            let index = i * 37 % 200;
+            if i % 3 == 0 {
+                assert_eq!(bits.remove(index), expected.remove(&index));
+            } else {
+                assert_eq!(bits.insert(index), expected.insert(index));
+            }
+            assert_eq!(bits.contains(index), expected.contains(&index));
+        }
+        assert_eq!(bits.count(), expected.len());
+        assert_eq!(
+            bits.iter().collect::<Vec<_>>(),
+            expected.into_iter().collect::<Vec<_>>()
+        );
+        assert!(!bits.contains(200));
+        assert!(!bits.remove(1000));
+    }
+
+    #[test]
+    fn set_operations() {
+        let set = |indices: &[usize]| {
+            let mut set = BitSet::new(130);
+            for &i in indices {
+                set.insert(i);
+            }
+            set
+        };
+        let (a, b) = (set(&[1, 64, 65, 129]), set(&[1, 2, 129]));
+
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(union, set(&[1, 2, 64, 65, 129]));
+
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(intersection, set(&[1, 129]));
+
+        let mut difference = a.clone();
+        difference.difference_with(&b);
+        assert_eq!(difference, set(&[64, 65]));
+
+        let mut symmetric = a.clone();
+        symmetric.symmetric_difference_with(&b);
+        assert_eq!(symmetric, set(&[2, 64, 65]));
+
+        assert!(intersection.is_subset(&a) && intersection.is_subset(&b));
+        assert!(!a.is_subset(&b));
+        assert!(difference.is_disjoint(&b));
+        assert!(set(&[]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "sets must have the same capacity")]
+    fn bit_set_rejects_different_capacities() {
+        let _ = BitSet::new(64).is_subset(&BitSet::new(128));
+    }
+
+    #[test]
+    fn bit_grid() {
+        let grid: Grid<u8> = "#.#\n..#\n".parse().unwrap();
+        let mut walls = BitGrid::from_grid(&grid, |&cell| cell == b'#');
+        assert_eq!(walls.count(), 3);
+        assert_eq!(
+            walls.iter().collect::<Vec<_>>(),
+            vec![(0, 0), (0, 2), (1, 2)]
+        );
+        assert!(walls.contains((1, 2)));
+        assert!(!walls.contains((1, 3)));
+        assert!(!walls.contains_signed(-1, 0));
+        assert_eq!(walls.to_string(), "#.#\n..#");
+
+        let mut visited = BitGrid::for_grid(&grid);
+        visited.insert((1, 0));
+        visited.insert((1, 1));
+        assert!(visited.is_disjoint(&walls));
+        walls.union_with(&visited);
+        assert_eq!(walls.count(), 5);
+        walls.difference_with(&visited);
+        assert_eq!(walls.count(), 3);
+        assert!(walls.remove((0, 0)));
+        assert!(!walls.remove((0, 0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "position (2, 0) is outside of the 3x2 grid")]
+    fn bit_grid_rejects_outside_positions() {
+        BitGrid::new(3, 2).insert((2, 0));
+    }
+
+    #[test]
+    fn directed_bit_grid() {
+        let mut states = DirectedBitGrid::new(4, 3);
+        let mut expected = HashSet::new();
+        for (i, &direction) in Direction::ALL.iter().enumerate() {
+            let position = (i % 3, i % 4);
+            assert!(states.insert(position, direction));
+            expected.insert((position, direction));
+        }
+        assert!(!states.insert((0, 0), Direction::Up));
+        assert!(states.insert((0, 0), Direction::Left));
+        expected.insert(((0, 0), Direction::Left));
+
+        assert_eq!(states.count(), expected.len());
+        assert_eq!(states.iter().collect::<HashSet<_>>(), expected);
+        assert!(states.contains((0, 0), Direction::Left));
+        assert!(!states.contains((0, 0), Direction::Down));
+        assert!(!states.contains((3, 0), Direction::Up));
+        assert_eq!(
+            states.positions().count(),
+            expected
+                .iter()
+                .map(|(p, _)| p)
+                .collect::<HashSet<_>>()
+                .len()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Connected-component labelling of a [`Grid`], e.g. to find the regions of equal plants in a garden.
+//!
+//! Cells are connected to their four orthogonal neighbours. Every cell belongs to exactly one component,
+//! components are numbered in row-major order of their first cell.
+use crate::{grid::Grid, union_find::UnionFind};
+
+/// A connected component of a grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub id: usize,
+    /// The first cell in row-major order.
+    pub first: (usize, usize),
+    /// The number of cells.
+    pub size: usize,
+    /// The number of cell edges that border another component or the outside of the grid.
+    pub perimeter: usize,
+    /// The top-left corner of the bounding box.
+    pub min: (usize, usize),
+    /// The bottom-right corner of the bounding box, inclusive.
+    pub max: (usize, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Components {
+    labels: Grid<usize>,
+    components: Vec<Component>,
+}
+
+impl Components {
+    /// The component id of every cell.
+    #[must_use]
+    pub fn labels(&self) -> &Grid<usize> {
+        &self.labels
+    }
+
+    /// The component id of the cell at `position`.
+    #[must_use]
+    pub fn label(&self, position: (usize, usize)) -> usize {
+        self.labels[position]
+    }
+
+    /// All components, indexed by id.
+    #[must_use]
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// The cells of component `id`, in row-major order.
+    pub fn cells(&self, id: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.labels
+            .iter()
+            .filter(move |&(_, &label)| label == id)
+            .map(|(position, _)| position)
+    }
+}
+
+/// Labels the components of cells with equal values.
+#[must_use]
+pub fn label<T: PartialEq>(grid: &Grid<T>) -> Components {
+    label_by(grid, |a, b| a == b)
+}
+
+/// Labels the components of cells where `connected` holds for every pair of neighbours joined together.
+#[must_use]
+pub fn label_by<T>(grid: &Grid<T>, connected: impl Fn(&T, &T) -> bool) -> Components {
+    let (width, height) = (grid.width(), grid.height());
+    let mut sets = UnionFind::new(grid.len());
+    for (row, col) in grid.positions() {
+        let index = row * width + col;
+        let cell = &grid[(row, col)];
+        if col + 1 < width && connected(cell, &grid[(row, col + 1)]) {
+            sets.union(index, index + 1);
+        }
+        if row + 1 < height && connected(cell, &grid[(row + 1, col)]) {
+            sets.union(index, index + width);
+        }
+    }
+
+    let mut id_of_root = vec![usize::MAX; grid.len()];
+    let mut components: Vec<Component> = vec![];
+    let mut labels = Vec::with_capacity(grid.len());
+    for position in grid.positions() {
+        let root = sets.find(labels.len());
+        if id_of_root[root] == usize::MAX {
+            id_of_root[root] = components.len();
+            components.push(Component {
+                id: components.len(),
+                first: position,
+                size: 0,
+                perimeter: 0,
+                min: position,
+                max: position,
+            });
+        }
+        let id = id_of_root[root];
+        let component = &mut components[id];
+        component.size += 1;
+        component.min = (
+            component.min.0.min(position.0),
+            component.min.1.min(position.1),
+        );
+        component.max = (
+            component.max.0.max(position.0),
+            component.max.1.max(position.1),
+        );
+        labels.push(id);
+    }
+
+    let labels = Grid::from_vec(width, height, labels).expect("one label per cell");
+    for (position, &id) in labels.iter() {
+        let same_neighbours = labels
+            .neighbours4(position)
+            .filter(|&neighbour| labels[neighbour] == id)
+            .count();
+        components[id].perimeter += 4 - same_neighbours;
+    }
+
+    Components { labels, components }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_regions() {
+        let grid: Grid<u8> = "AAAA\nBBCD\nBBCC\nEEEC".parse().unwrap();
+        let components = label(&grid);
+
+        let summary: Vec<_> = components
+            .components()
+            .iter()
+            .map(|c| (grid[c.first], c.size, c.perimeter))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (b'A', 4, 10),
+                (b'B', 4, 8),
+                (b'C', 4, 10),
+                (b'D', 1, 4),
+                (b'E', 3, 8),
+            ]
+        );
+        assert_eq!(components.label((2, 3)), components.label((1, 2)));
+        assert_eq!(components.components()[2].min, (1, 2));
+        assert_eq!(components.components()[2].max, (3, 3));
+        assert_eq!(
+            components.cells(4).collect::<Vec<_>>(),
+            vec![(3, 0), (3, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn separates_equal_values() {
+        // the outer O region surrounds four separate X regions.
+        let grid: Grid<u8> = "OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO".parse().unwrap();
+        let components = label(&grid);
+        assert_eq!(components.len(), 5);
+        let outer = &components.components()[0];
+        assert_eq!((outer.size, outer.perimeter), (21, 36));
+        assert_eq!((outer.min, outer.max), ((0, 0), (4, 4)));
+        assert!(components.components()[1..]
+            .iter()
+            .all(|c| c.size == 1 && c.perimeter == 4));
+
+        let connected = label_by(&grid, |_, _| true);
+        assert_eq!(connected.len(), 1);
+        assert_eq!(connected.components()[0].perimeter, 20);
+    }
+}
@@ -0,0 +1,283 @@
+//! Cycle detection for iterated state functions `x, f(x), f(f(x)), ...`.
+use std::{collections::HashMap, hash::Hash};
+
+/// A cycle in a sequence of states: the state at step `start + length` equals the state at step `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Index of the first state that is part of the cycle.
+    pub start: usize,
+    /// Number of steps until a state repeats.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Maps step `n` to the earliest step with the same state, i.e. a step smaller than `start + length`.
+    #[must_use]
+    pub fn state_index(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Floyd's tortoise and hare algorithm. Uses constant memory, but the function has to be deterministic.
+///
+/// Loops forever if the sequence does not cycle, which can not happen for a finite state space.
+pub fn floyd<T: Clone + PartialEq>(x0: T, f: impl Fn(&T) -> T) -> Cycle {
+    try_floyd(x0, |x| Some(f(x))).unwrap()
+}
+
+/// Like [`floyd`], for sequences that may end: returns `None` as soon as `f` does.
+pub fn try_floyd<T: Clone + PartialEq>(x0: T, f: impl Fn(&T) -> Option<T>) -> Option<Cycle> {
+    let mut tortoise = f(&x0)?;
+    let mut hare = f(&tortoise)?;
+    while tortoise != hare {
+        tortoise = f(&tortoise)?;
+        hare = f(&f(&hare)?)?;
+    }
+
+    let mut start = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = f(&tortoise)?;
+        hare = f(&hare)?;
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = f(&tortoise)?;
+    while tortoise != hare {
+        hare = f(&hare)?;
+        length += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Brent's algorithm. Uses constant memory and usually fewer function evaluations than [`floyd`].
+///
+/// Loops forever if the sequence does not cycle, which can not happen for a finite state space.
+pub fn brent<T: Clone + PartialEq>(x0: T, f: impl Fn(&T) -> T) -> Cycle {
+    try_brent(x0, |x| Some(f(x))).unwrap()
+}
+
+/// Like [`brent`], for sequences that may end: returns `None` as soon as `f` does.
+///
+/// This answers "does this simulation loop?" without keeping a set of visited states.
+pub fn try_brent<T: Clone + PartialEq>(x0: T, f: impl Fn(&T) -> Option<T>) -> Option<Cycle> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = x0.clone();
+    let mut hare = f(&x0)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = f(&hare)?;
+        length += 1;
+    }
+
+    tortoise = x0.clone();
+    hare = x0;
+    for _ in 0..length {
+        hare = f(&hare)?;
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise)?;
+        hare = f(&hare)?;
+        start += 1;
+    }
+
+    Some(Cycle { start, length })
+}
+
+/// Simulates until a state repeats, returning every state before the repetition and the cycle.
+///
+/// States are compared by `key`, which allows ignoring parts of the state that do not influence the next step.
+pub fn find_repeat<T, K: Eq + Hash>(
+    x0: T,
+    mut f: impl FnMut(&T) -> T,
+    mut key: impl FnMut(&T) -> K,
+) -> (Vec<T>, Cycle) {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut history = vec![];
+    let mut state = x0;
+
+    loop {
+        let step = history.len();
+        if let Some(&start) = seen.get(&key(&state)) {
+            let cycle = Cycle {
+                start,
+                length: step - start,
+            };
+            return (history, cycle);
+        }
+        seen.insert(key(&state), step);
+        let next = f(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+/// Returns the state after `n` steps, extrapolating once a state repeats.
+pub fn nth_state<T: Clone + Eq + Hash>(x0: T, f: impl FnMut(&T) -> T, n: usize) -> T {
+    nth_state_by_key(x0, f, T::clone, n)
+}
+
+/// Like [`nth_state`], comparing states by `key`.
+///
+/// Once a key repeats, the result is the earlier state with the same key as the state after `n` steps. Only the
+/// part of the returned state covered by `key` is meaningful then, everything else, e.g. a step counter, is stale.
+pub fn nth_state_by_key<T: Clone, K: Eq + Hash>(
+    x0: T,
+    mut f: impl FnMut(&T) -> T,
+    mut key: impl FnMut(&T) -> K,
+    n: usize,
+) -> T {
+    let mut seen: HashMap<K, usize> = HashMap::new();
+    let mut history = vec![];
+    let mut state = x0;
+
+    for step in 0..n {
+        if let Some(&start) = seen.get(&key(&state)) {
+            let cycle = Cycle {
+                start,
+                length: step - start,
+            };
+            return history.swap_remove(cycle.state_index(n));
+        }
+        seen.insert(key(&state), step);
+        let next = f(&state);
+        history.push(state);
+        state = next;
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    /// Finds the cycle by recording every state.
+    fn naive(x0: u32, f: impl Fn(&u32) -> u32) -> Cycle {
+        let mut history = vec![x0];
+        loop {
+            let next = f(history.last().unwrap());
+            if let Some(start) = history.iter().position(|&x| x == next) {
+                return Cycle {
+                    start,
+                    length: history.len() - start,
+                };
+            }
+            history.push(next);
+        }
+    }
+
+    #[test]
+    fn detects_cycles() {
+        for x0 in 0..255 {
+            let expected = naive(x0, step);
+            assert_eq!(floyd(x0, step), expected, "floyd from {x0}");
+            assert_eq!(brent(x0, step), expected, "brent from {x0}");
+            assert_eq!(find_repeat(x0, step, |&x| x).1, expected);
+        }
+    }
+
+    #[test]
+    fn detects_pure_cycles() {
+        let rotate = |x: &u8| (x + 1) % 5;
+        assert_eq!(
+            brent(2, rotate),
+            Cycle {
+                start: 0,
+                length: 5
+            }
+        );
+        assert_eq!(
+            floyd(2, rotate),
+            Cycle {
+                start: 0,
+                length: 5
+            }
+        );
+        assert_eq!(
+            brent(7, |_| 7),
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn stops_at_terminating_sequences() {
+        let countdown = |x: &u32| x.checked_sub(1);
+        assert_eq!(try_brent(10, countdown), None);
+        assert_eq!(try_floyd(10, countdown), None);
+        assert_eq!(
+            try_brent(10_u32, |x| Some(x.saturating_sub(1))),
+            Some(Cycle {
+                start: 10,
+                length: 1
+            })
+        );
+    }
+
+    #[test]
+    fn extrapolates_states() {
+        for x0 in [0, 3, 17, 200] {
+            let mut expected = x0;
+            for n in 0..600 {
+                assert_eq!(nth_state(x0, step, n), expected, "step {n} from {x0}");
+                expected = step(&expected);
+            }
+        }
+        assert_eq!(
+            nth_state(0_u64, |x| (x + 1) % 7, 1_000_000_000),
+            1_000_000_000 % 7
+        );
+    }
+
+    #[test]
+    fn compares_states_by_key() {
+        // the counter does not influence the next step, so only the position is compared.
+        let f = |&(position, counter): &(u8, u64)| ((position + 1) % 3, counter + 1);
+        let (history, cycle) = find_repeat((0, 0), f, |&(position, _)| position);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 0,
+                length: 3
+            }
+        );
+        assert_eq!(history.len(), 3);
+        // the counter is the one of the earlier state at position 1, not 10.
+        assert_eq!(
+            nth_state_by_key((0, 0), f, |&(position, _)| position, 10),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    fn maps_steps_into_cycle() {
+        let cycle = Cycle {
+            start: 2,
+            length: 3,
+        };
+        assert_eq!(
+            (0..9).map(|n| cycle.state_index(n)).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 2, 3, 4, 2]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Debug output for solutions that stays out of answers and benchmarks.
+//!
+//! [`debug!`](crate::debug) prints to stderr, prefixed with the day and part being solved, but only when the
+//! solution runs with `--debug`:
+//!
+//! ```ignore
+//! advent_of_code::debug!("skipping mul({a},{b})");
+//! // stderr: [day 03 part 2] skipping mul(5,5)
+//! ```
+//!
+//! Release builds compile the output away, so `cargo time` never measures it. The runner also disables
+//! output while benchmarking, so only the first run of each part is logged.
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use crate::template::Day;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static DAY: AtomicU8 = AtomicU8::new(0);
+static PART: AtomicU8 = AtomicU8::new(0);
+
+/// Whether [`debug!`](crate::debug) currently prints anything.
+#[must_use]
+pub fn is_enabled() -> bool {
+    cfg!(debug_assertions) && ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turns output on or off. Called by the runner, solutions only log.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether `--debug` was passed to the solution binary.
+#[must_use]
+pub fn from_args() -> bool {
+    std::env::args().any(|x| x == "--debug")
+}
+
+/// Sets the day and part shown in front of every message.
+pub fn set_context(day: Day, part: u8) {
+    DAY.store(day.into_inner(), Ordering::Relaxed);
+    PART.store(part, Ordering::Relaxed);
+}
+
+/// The `[day NN part N]` prefix of the current context, empty outside of a solution run.
+#[must_use]
+pub fn prefix() -> String {
+    match (
+        Day::new(DAY.load(Ordering::Relaxed)),
+        PART.load(Ordering::Relaxed),
+    ) {
+        (Some(day), 0) => format!("[day {day}] "),
+        (Some(day), part) => format!("[day {day} part {part}] "),
+        (None, _) => String::new(),
+    }
+}
+
+/// Prints a line to stderr when debug output is enabled, taking the same arguments as `eprintln!`.
+///
+/// The arguments are not evaluated otherwise, and release builds drop the call entirely.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::debug::is_enabled() {
+            ::std::eprintln!("{}{}", $crate::debug::prefix(), ::std::format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_messages_with_context() {
+        // other tests may run solutions, so leave the global state as it was found.
+        let (day, part) = (DAY.load(Ordering::Relaxed), PART.load(Ordering::Relaxed));
+        let enabled = ENABLED.load(Ordering::Relaxed);
+
+        set_context(Day::new(3).unwrap(), 2);
+        assert_eq!(prefix(), "[day 03 part 2] ");
+
+        set_enabled(true);
+        assert_eq!(is_enabled(), cfg!(debug_assertions));
+        set_enabled(false);
+
+        let mut evaluated = false;
+        crate::debug!("{}", {
+            evaluated = true;
+            1
+        });
+        assert!(!evaluated);
+
+        DAY.store(day, Ordering::Relaxed);
+        PART.store(part, Ordering::Relaxed);
+        set_enabled(enabled);
+    }
+}
@@ -0,0 +1,229 @@
+//! Searches for operators that turn a list of operands into a target value, e.g. `190: 10 19` with `10 * 19`.
+//!
+//! Expressions are evaluated strictly left to right, without operator precedence. When every operator has an
+//! inverse, the search runs backwards from the target, which prunes most branches after the last operand.
+use std::fmt::{self, Debug};
+
+use crate::math;
+
+/// The result of inverting an operator, see [`Operator::invert`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inverse<T> {
+    /// No left operand gives the result.
+    Impossible,
+    /// Exactly this left operand gives the result.
+    Unique(T),
+    /// Every left operand gives the result, e.g. `x * 0 = 0`.
+    Any,
+}
+
+/// A binary operator. `apply` returns `None` when the result is not representable, e.g. on overflow.
+#[derive(Clone, Copy)]
+pub struct Operator<T> {
+    pub name: &'static str,
+    /// `apply(left, right)` is the result of `left <op> right`.
+    pub apply: fn(T, T) -> Option<T>,
+    /// `invert(result, right)` describes the `left` operands with `left <op> right == result`.
+    pub invert: Option<fn(T, T) -> Inverse<T>>,
+}
+
+impl<T> Debug for Operator<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Operator").field(&self.name).finish()
+    }
+}
+
+impl<T> From<Option<T>> for Inverse<T> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Inverse::Impossible, Inverse::Unique)
+    }
+}
+
+impl Operator<u64> {
+    pub const ADD: Self = Self {
+        name: "+",
+        apply: u64::checked_add,
+        invert: Some(|result, right| result.checked_sub(right).into()),
+    };
+
+    pub const MUL: Self = Self {
+        name: "*",
+        apply: u64::checked_mul,
+        invert: Some(|result, right| match (result, right) {
+            (0, 0) => Inverse::Any,
+            (_, 0) => Inverse::Impossible,
+            _ => (result % right == 0).then(|| result / right).into(),
+        }),
+    };
+
+    /// Concatenates the decimal digits, e.g. `12 || 345 = 12345`.
+    pub const CONCAT: Self = Self {
+        name: "||",
+        apply: math::concat,
+        invert: Some(|result, right| math::strip_suffix(result, right).into()),
+    };
+}
+
+/// Finds operator combinations for `operands` that evaluate to a target.
+#[derive(Debug, Clone)]
+pub struct Solver<T> {
+    operators: Vec<Operator<T>>,
+    backwards: bool,
+}
+
+impl<T: Copy + PartialEq> Solver<T> {
+    #[must_use]
+    pub fn new(operators: impl IntoIterator<Item = Operator<T>>) -> Self {
+        let operators: Vec<_> = operators.into_iter().collect();
+        let backwards = operators.iter().all(|op| op.invert.is_some());
+        Self {
+            operators,
+            backwards,
+        }
+    }
+
+    /// Whether any combination of operators evaluates to `target`. Stops at the first one found.
+    #[must_use]
+    pub fn is_solvable(&self, target: T, operands: &[T]) -> bool {
+        self.search(target, operands, true) > 0
+    }
+
+    /// The number of operator combinations that evaluate to `target`.
+    #[must_use]
+    pub fn count_solutions(&self, target: T, operands: &[T]) -> usize {
+        self.search(target, operands, false)
+    }
+
+    fn search(&self, target: T, operands: &[T], first_only: bool) -> usize {
+        match operands.split_first() {
+            None => 0,
+            Some((&first, rest)) if self.backwards => {
+                self.backward(target, first, rest, first_only)
+            }
+            Some((&first, rest)) => self.forward(Some(target), first, rest, first_only),
+        }
+    }
+
+    /// Unwinds the operands from the end: `target` has to be `left <op> last` for some operator.
+    fn backward(&self, target: T, first: T, rest: &[T], first_only: bool) -> usize {
+        let Some((&last, rest)) = rest.split_last() else {
+            return usize::from(target == first);
+        };
+
+        let mut count = 0;
+        for op in &self.operators {
+            let invert = op.invert.expect("backward search requires inverses");
+            count += match invert(target, last) {
+                Inverse::Impossible => 0,
+                Inverse::Unique(left) => self.backward(left, first, rest, first_only),
+                Inverse::Any => self.forward(None, first, rest, first_only),
+            };
+            if first_only && count > 0 {
+                break;
+            }
+        }
+        count
+    }
+
+    /// Evaluates every combination, counting those that give `target`, or all that can be evaluated if `None`.
+    fn forward(&self, target: Option<T>, value: T, rest: &[T], first_only: bool) -> usize {
+        let Some((&next, rest)) = rest.split_first() else {
+            return usize::from(target.is_none_or(|target| target == value));
+        };
+
+        let mut count = 0;
+        for op in &self.operators {
+            if let Some(value) = (op.apply)(value, next) {
+                count += self.forward(target, value, rest, first_only);
+                if first_only && count > 0 {
+                    break;
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EQUATIONS: [(u64, &[u64]); 9] = [
+        (190, &[10, 19]),
+        (3267, &[81, 40, 27]),
+        (83, &[17, 5]),
+        (156, &[15, 6]),
+        (7290, &[6, 8, 6, 15]),
+        (161011, &[16, 10, 13]),
+        (192, &[17, 8, 14]),
+        (21037, &[9, 7, 18, 13]),
+        (292, &[11, 6, 16, 20]),
+    ];
+
+    /// The same operators, without inverses, to force the forward search.
+    fn forward_only(operators: &[Operator<u64>]) -> Solver<u64> {
+        Solver::new(operators.iter().map(|&op| Operator { invert: None, ..op }))
+    }
+
+    #[test]
+    fn solves_equations() {
+        for operators in [
+            &[Operator::ADD, Operator::MUL][..],
+            &[Operator::ADD, Operator::MUL, Operator::CONCAT][..],
+        ] {
+            let backward = Solver::new(operators.iter().copied());
+            let forward = forward_only(operators);
+
+            for (target, operands) in EQUATIONS {
+                assert_eq!(
+                    backward.count_solutions(target, operands),
+                    forward.count_solutions(target, operands),
+                    "{target}: {operands:?} with {operators:?}"
+                );
+                assert_eq!(
+                    backward.is_solvable(target, operands),
+                    forward.is_solvable(target, operands)
+                );
+            }
+        }
+
+        let solver = Solver::new([Operator::ADD, Operator::MUL]);
+        let solvable: Vec<_> = EQUATIONS
+            .iter()
+            .filter(|(target, operands)| solver.is_solvable(*target, operands))
+            .map(|(target, _)| target)
+            .collect();
+        assert_eq!(solvable, vec![&190, &3267, &292]);
+        assert_eq!(solver.count_solutions(3267, &[81, 40, 27]), 2);
+    }
+
+    #[test]
+    fn handles_edge_cases() {
+        let solver = Solver::new([Operator::ADD, Operator::MUL, Operator::CONCAT]);
+        assert!(solver.is_solvable(7, &[7]));
+        assert!(!solver.is_solvable(7, &[]));
+        // 0 + 5 and 0 || 5 both give 5, and 5 * 0 must not divide by zero.
+        assert_eq!(solver.count_solutions(5, &[0, 5]), 2);
+        assert_eq!(solver.count_solutions(0, &[5, 0]), 1);
+        // x * 0 = 0 for every x, the prefix only has to be computable.
+        assert_eq!(solver.count_solutions(0, &[3, 4, 0]), 3);
+        assert_eq!(
+            forward_only(&[Operator::ADD, Operator::MUL, Operator::CONCAT])
+                .count_solutions(0, &[3, 4, 0]),
+            3
+        );
+        assert!(!solver.is_solvable(u64::MAX, &[u64::MAX, 2]));
+        assert!(!forward_only(&[Operator::MUL]).is_solvable(0, &[u64::MAX, 2]));
+    }
+
+    #[test]
+    fn concatenates() {
+        assert_eq!((Operator::CONCAT.apply)(12, 345), Some(12345));
+        assert_eq!((Operator::CONCAT.apply)(12, 0), Some(120));
+        assert_eq!((Operator::CONCAT.apply)(u64::MAX, 1), None);
+        let invert = Operator::CONCAT.invert.unwrap();
+        assert_eq!(invert(12345, 345), Inverse::Unique(12));
+        assert_eq!(invert(12345, 45), Inverse::Unique(123));
+        assert_eq!(invert(12345, 44), Inverse::Impossible);
+    }
+}
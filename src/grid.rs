@@ -0,0 +1,566 @@
+//! A two-dimensional grid backed by a flat buffer.
+//!
+//! Positions are `(row, col)` tuples, with `(0, 0)` in the top-left corner.
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::point::{Coord, Point};
+
+/// Offsets of the four orthogonal neighbours, clockwise starting at the top.
+pub const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Offsets of all eight neighbours, clockwise starting at the top.
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+/// An error which can be returned when parsing a [`Grid`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// A line has a different length than the first line. Line numbers start at 1.
+    RaggedLine {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The number of cells does not match the dimensions.
+    SizeMismatch { expected: usize, found: usize },
+}
+
+impl Error for GridError {}
+
+impl Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::RaggedLine {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} has {found} cells, expected {expected} like the first line"
+            ),
+            GridError::SizeMismatch { expected, found } => {
+                write!(f, "expected {expected} cells, found {found}")
+            }
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from row-major cells.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self, GridError> {
+        if cells.len() != width * height {
+            return Err(GridError::SizeMismatch {
+                expected: width * height,
+                found: cells.len(),
+            });
+        }
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    /// Parses a grid from text, converting every byte with `f`.
+    ///
+    /// All lines have to be of the same length. Empty lines at the end of the input are ignored.
+    pub fn parse_with(input: &str, f: impl Fn(u8) -> T) -> Result<Self, GridError> {
+        let mut cells = vec![];
+        let mut width = 0;
+        let mut height = 0;
+
+        for (i, line) in input.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            if i == 0 {
+                width = line.len();
+            } else if line.len() != width {
+                return Err(GridError::RaggedLine {
+                    line: i + 1,
+                    expected: width,
+                    found: line.len(),
+                });
+            }
+            cells.extend(line.bytes().map(&f));
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width,
+            height,
+        })
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the cells in row-major order.
+    #[must_use]
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    #[must_use]
+    pub fn in_bounds(&self, row: isize, col: isize) -> bool {
+        row >= 0 && col >= 0 && (row as usize) < self.height && (col as usize) < self.width
+    }
+
+    /// Converts a position into an index of the flat buffer, if it is inside the grid.
+    #[must_use]
+    pub fn index_of(&self, (row, col): (usize, usize)) -> Option<usize> {
+        (row < self.height && col < self.width).then_some(row * self.width + col)
+    }
+
+    /// Converts an index of the flat buffer into a position.
+    #[must_use]
+    pub fn position_of(&self, index: usize) -> (usize, usize) {
+        (index / self.width, index % self.width)
+    }
+
+    #[must_use]
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.index_of((row, col)).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        self.index_of((row, col)).map(|i| &mut self.cells[i])
+    }
+
+    /// Like [`Grid::get`], but accepts positions that may lie outside the grid on any side.
+    #[must_use]
+    pub fn get_signed(&self, row: isize, col: isize) -> Option<&T> {
+        if self.in_bounds(row, col) {
+            self.get(row as usize, col as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the cell at a point, if it is inside the grid.
+    #[must_use]
+    pub fn get_point<C: Coord>(&self, point: Point<C>) -> Option<&T> {
+        let (row, col) = point.to_grid()?;
+        self.get(row, col)
+    }
+
+    /// Sets a cell, returning the previous value. Returns `None` if the position is outside the grid.
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> Option<T> {
+        self.get_mut(row, col)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Returns the position offset by `(d_row, d_col)`, if it is inside the grid.
+    #[must_use]
+    pub fn offset(
+        &self,
+        (row, col): (usize, usize),
+        (d_row, d_col): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(d_row)?;
+        let col = col.checked_add_signed(d_col)?;
+        (row < self.height && col < self.width).then_some((row, col))
+    }
+
+    /// Iterates all positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Iterates all cells along with their positions.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Iterates the orthogonal neighbours of a position that are inside the grid.
+    pub fn neighbours4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS_4
+            .iter()
+            .filter_map(move |&offset| self.offset(position, offset))
+    }
+
+    /// Iterates the orthogonal and diagonal neighbours of a position that are inside the grid.
+    pub fn neighbours8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS_8
+            .iter()
+            .filter_map(move |&offset| self.offset(position, offset))
+    }
+
+    /// Walks from a position in a direction, yielding every position until the edge of the grid.
+    /// The start position itself is included.
+    pub fn ray(
+        &self,
+        start: (usize, usize),
+        step: (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::successors(self.index_of(start).map(|_| start), move |&position| {
+            self.offset(position, step)
+        })
+    }
+
+    #[must_use]
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a chunk size of zero.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterates the cells of a column. Yields nothing if the column is outside the grid.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        let len = if col < self.width { self.height } else { 0 };
+        self.cells
+            .iter()
+            .skip(col)
+            .step_by(self.width.max(1))
+            .take(len)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |col| self.column(col))
+    }
+
+    /// Iterates the diagonals running from the top-left to the bottom-right,
+    /// starting with the one in the bottom-left corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let starts = (0..self.height)
+            .rev()
+            .map(|row| (row, 0))
+            .chain((1..self.width).map(|col| (0, col)));
+        starts.map(move |start| self.ray(start, (1, 1)).map(|(row, col)| &self[(row, col)]))
+    }
+
+    /// Iterates the diagonals running from the top-right to the bottom-left,
+    /// starting with the one in the top-left corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let last_col = self.width.saturating_sub(1);
+        let starts = (0..self.width)
+            .map(|col| (0, col))
+            .chain((1..self.height).map(move |row| (row, last_col)));
+        starts.map(move |start| self.ray(start, (1, -1)).map(|(row, col)| &self[(row, col)]))
+    }
+
+    /// Returns the position of the first cell (in row-major order) that matches the predicate.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|i| self.position_of(i))
+    }
+
+    /// Creates a new grid by applying `f` to every cell.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Returns the position of the first cell (in row-major order) equal to `value`.
+    #[must_use]
+    pub fn find(&self, value: &T) -> Option<(usize, usize)> {
+        self.position(|cell| cell == value)
+    }
+
+    /// Iterates the positions of all cells equal to `value`.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(position, _)| position)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    #[must_use]
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal.
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        Self {
+            cells: (0..self.width)
+                .flat_map(|col| self.column(col).cloned())
+                .collect(),
+            width: self.height,
+            height: self.width,
+        }
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self {
+        Self {
+            cells: (0..self.width)
+                .flat_map(|col| {
+                    (0..self.height)
+                        .rev()
+                        .map(move |row| self[(row, col)].clone())
+                })
+                .collect(),
+            width: self.height,
+            height: self.width,
+        }
+    }
+
+    /// Rotates the grid by 90 degrees counter-clockwise.
+    #[must_use]
+    pub fn rotate_counterclockwise(&self) -> Self {
+        Self {
+            cells: (0..self.width)
+                .rev()
+                .flat_map(|col| self.column(col).cloned())
+                .collect(),
+            width: self.height,
+            height: self.width,
+        }
+    }
+}
+
+/// Panics if the position is outside the grid. Use [`Grid::get`] for checked access.
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(
+            row < self.height && col < self.width,
+            "position ({row}, {col}) is outside of the {}x{} grid",
+            self.width,
+            self.height
+        );
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            row < self.height && col < self.width,
+            "position ({row}, {col}) is outside of the {}x{} grid",
+            self.width,
+            self.height
+        );
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+impl FromStr for Grid<u8> {
+    type Err = GridError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, |byte| byte)
+    }
+}
+
+/// Cell types that can be displayed as a single character.
+pub trait GridCell {
+    fn to_char(&self) -> char;
+}
+
+impl GridCell for u8 {
+    fn to_char(&self) -> char {
+        char::from(*self)
+    }
+}
+
+impl GridCell for char {
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+impl GridCell for bool {
+    fn to_char(&self) -> char {
+        if *self {
+            '#'
+        } else {
+            '.'
+        }
+    }
+}
+
+impl<T: GridCell> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "abc\ndef\n";
+
+    fn grid() -> Grid<u8> {
+        INPUT.parse().unwrap()
+    }
+
+    fn to_string<'a>(cells: impl Iterator<Item = &'a u8>) -> String {
+        cells.map(|&byte| char::from(byte)).collect()
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], b'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+        assert_eq!(
+            "ab\r\ncd\r\n".parse::<Grid<u8>>().unwrap().to_string(),
+            "ab\ncd"
+        );
+        assert!("".parse::<Grid<u8>>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_ragged_lines() {
+        assert_eq!(
+            "abc\nde\nfgh".parse::<Grid<u8>>(),
+            Err(GridError::RaggedLine {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert!(Grid::from_vec(2, 2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn checks_bounds() {
+        let mut grid = grid();
+        assert_eq!(grid.get(1, 3), None);
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get_signed(-1, 0), None);
+        assert_eq!(grid.get_signed(1, 1), Some(&b'e'));
+        assert_eq!(grid.get_point(Point::new(2, 1)), Some(&b'f'));
+        assert_eq!(grid.get_point(Point::new(-1, 1)), None);
+        assert_eq!(grid.set(0, 0, b'x'), Some(b'a'));
+        assert_eq!(grid.set(0, 3, b'x'), None);
+        assert_eq!(grid.to_string(), "xbc\ndef");
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of the 3x2 grid")]
+    fn panics_on_unchecked_out_of_bounds() {
+        let _ = grid()[(0, 3)];
+    }
+
+    #[test]
+    fn finds_neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            grid.neighbours8((1, 1)).collect::<Vec<_>>(),
+            vec![(0, 1), (0, 2), (1, 2), (1, 0), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn iterates_lines() {
+        let grid = grid();
+        assert_eq!(grid.row(1), b"def");
+        assert_eq!(to_string(grid.column(1)), "be");
+        assert_eq!(to_string(grid.column(3)), "");
+        assert_eq!(
+            grid.columns().map(to_string).collect::<Vec<_>>(),
+            vec!["ad", "be", "cf"]
+        );
+        assert_eq!(
+            grid.diagonals().map(to_string).collect::<Vec<_>>(),
+            vec!["d", "ae", "bf", "c"]
+        );
+        assert_eq!(
+            grid.anti_diagonals().map(to_string).collect::<Vec<_>>(),
+            vec!["a", "bd", "ce", "f"]
+        );
+        assert_eq!(
+            grid.ray((0, 2), (0, -1)).collect::<Vec<_>>(),
+            vec![(0, 2), (0, 1), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn finds_cells() {
+        let grid: Grid<u8> = "a.a\n.a.".parse().unwrap();
+        assert_eq!(grid.find(&b'.'), Some((0, 1)));
+        assert_eq!(grid.find(&b'x'), None);
+        assert_eq!(
+            grid.find_all(&b'a').collect::<Vec<_>>(),
+            vec![(0, 0), (0, 2), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn transforms_grids() {
+        let grid = grid();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+        assert_eq!(grid.map(|&byte| byte == b'e').to_string(), "...\n.#.");
+    }
+}
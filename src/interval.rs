@@ -0,0 +1,351 @@
+//! Sets of values stored as sorted, disjoint half-open ranges, e.g. seed ranges or scanner coverage.
+use std::ops::{Add, Range, Sub};
+
+/// A set of values stored as the fewest possible `start..end` ranges.
+///
+/// The ranges are sorted, non-empty and neither overlap nor touch, so equal sets always have equal ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// The ranges in ascending order.
+    #[must_use]
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Adds all values of `range`, merging it with overlapping and adjacent ranges.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // ranges ending before the new one starts stay, the ones starting after it ends as well.
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Removes all values of `range`, splitting ranges that only partially overlap it.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first == last {
+            return;
+        }
+
+        let mut kept = Vec::with_capacity(2);
+        if self.ranges[first].start < range.start {
+            kept.push(self.ranges[first].start..range.start);
+        }
+        if range.end < self.ranges[last - 1].end {
+            kept.push(range.end..self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, kept);
+    }
+
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Whether every value of `range` is in the set. Empty ranges are always contained.
+    #[must_use]
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        self.ranges
+            .get(i)
+            .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// Whether any value of `range` is in the set.
+    #[must_use]
+    pub fn overlaps(&self, range: &Range<T>) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        !range.is_empty() && self.ranges.get(i).is_some_and(|r| r.start < range.end)
+    }
+
+    /// The values in either set.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    /// The values in both sets.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a.start.max(b.start), a.end.min(b.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The values in `self` but not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    /// The values of `within` that are not in the set, e.g. the gaps in a scanner's coverage.
+    #[must_use]
+    pub fn complement(&self, within: Range<T>) -> Self {
+        let mut complement = Self::new();
+        complement.insert(within);
+        complement.difference(self)
+    }
+
+    /// The smallest value in the set.
+    #[must_use]
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T> + Default> IntervalSet<T> {
+    /// The number of values in the set, i.e. the total length of all ranges.
+    #[must_use]
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::default(), |total, r| total + (r.end - r.start))
+    }
+}
+
+/// A piecewise mapping of values, e.g. a `destination source length` table. Values outside of every
+/// source range map to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappingTable<T> {
+    /// `(source, destination start)`, sorted by source start.
+    entries: Vec<(Range<T>, T)>,
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> FromIterator<(Range<T>, T)>
+    for MappingTable<T>
+{
+    fn from_iter<I: IntoIterator<Item = (Range<T>, T)>>(iter: I) -> Self {
+        let mut entries: Vec<_> = iter.into_iter().filter(|(r, _)| !r.is_empty()).collect();
+        entries.sort_by_key(|(r, _)| r.start);
+        assert!(
+            entries.windows(2).all(|w| w[0].0.end <= w[1].0.start),
+            "source ranges of a mapping table must not overlap"
+        );
+        Self { entries }
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> MappingTable<T> {
+    /// Maps a single value.
+    #[must_use]
+    pub fn map(&self, value: T) -> T {
+        let i = self.entries.partition_point(|(r, _)| r.end <= value);
+        match self.entries.get(i) {
+            Some((source, destination)) if source.start <= value => {
+                value - source.start + *destination
+            }
+            _ => value,
+        }
+    }
+
+    /// Splits `range` at the boundaries of the source ranges, returning every piece in ascending order
+    /// together with the range it maps to.
+    #[must_use]
+    pub fn split(&self, range: Range<T>) -> Vec<(Range<T>, Range<T>)> {
+        let mut pieces = vec![];
+        let mut start = range.start;
+        let first = self.entries.partition_point(|(r, _)| r.end <= start);
+        for (source, destination) in &self.entries[first..] {
+            if start >= range.end || source.start >= range.end {
+                break;
+            }
+            if start < source.start {
+                pieces.push((start..source.start, start..source.start));
+                start = source.start;
+            }
+            let end = source.end.min(range.end);
+            let shift = |v: T| v - source.start + *destination;
+            pieces.push((start..end, shift(start)..shift(end)));
+            start = end;
+        }
+        if start < range.end {
+            pieces.push((start..range.end, start..range.end));
+        }
+        pieces
+    }
+
+    /// Maps every range of `set`, splitting ranges that span several entries.
+    #[must_use]
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.ranges()
+            .iter()
+            .flat_map(|range| self.split(range.clone()))
+            .map(|(_, mapped)| mapped)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All values are below this bound, so a set fits into the bits of a `u8` and every set can be checked.
+    const MAX: u32 = 8;
+
+    fn bitmap(range: &Range<u32>) -> u8 {
+        range.clone().fold(0, |bits, v| bits | 1 << v)
+    }
+
+    fn to_bitmap(set: &IntervalSet<u32>) -> u8 {
+        set.ranges().iter().fold(0, |bits, r| bits | bitmap(r))
+    }
+
+    /// Every set is the same as its bitmap and in canonical form.
+    fn check(set: &IntervalSet<u32>, expected: u8) {
+        assert_eq!(to_bitmap(set), expected, "{set:?}");
+        assert!(set.ranges().iter().all(|r| !r.is_empty()));
+        assert!(set.ranges().windows(2).all(|w| w[0].end < w[1].start));
+        assert_eq!(set.total_len(), expected.count_ones());
+        for v in 0..MAX {
+            assert_eq!(set.contains(v), expected & 1 << v != 0);
+        }
+    }
+
+    fn all_ranges() -> impl Iterator<Item = Range<u32>> {
+        (0..=MAX).flat_map(|start| (start..=MAX).map(move |end| start..end))
+    }
+
+    fn from_bitmap(bits: u8) -> IntervalSet<u32> {
+        (0..MAX)
+            .filter(|v| bits & 1 << v != 0)
+            .map(|v| v..v + 1)
+            .collect()
+    }
+
+    #[test]
+    fn matches_bitmap_model() {
+        for bits in 0..=u8::MAX {
+            let set = from_bitmap(bits);
+            check(&set, bits);
+
+            for range in all_ranges() {
+                let mut inserted = set.clone();
+                inserted.insert(range.clone());
+                check(&inserted, bits | bitmap(&range));
+
+                let mut removed = set.clone();
+                removed.remove(range.clone());
+                check(&removed, bits & !bitmap(&range));
+
+                assert_eq!(
+                    set.contains_range(&range),
+                    bits & bitmap(&range) == bitmap(&range)
+                );
+                assert_eq!(set.overlaps(&range), bits & bitmap(&range) != 0);
+                check(&set.complement(range.clone()), bitmap(&range) & !bits);
+            }
+        }
+    }
+
+    #[test]
+    fn set_operations_match_bitmap_model() {
+        for a in 0..=u8::MAX {
+            for b in 0..=u8::MAX {
+                let (x, y) = (from_bitmap(a), from_bitmap(b));
+                check(&x.union(&y), a | b);
+                check(&x.intersection(&y), a & b);
+                check(&x.difference(&y), a & !b);
+            }
+        }
+    }
+
+    #[test]
+    fn merges_ranges() {
+        let set: IntervalSet<i64> = [5..10, -3..0, 0..2, 8..12, 20..20].into_iter().collect();
+        assert_eq!(set.ranges(), &[-3..2, 5..12]);
+        assert_eq!(set.total_len(), 12);
+        assert_eq!(set.min(), Some(-3));
+        assert_eq!(set.complement(-5..15).ranges(), &[-5..-3, 2..5, 12..15]);
+    }
+
+    #[test]
+    fn maps_ranges() {
+        // seed-to-soil map: `50 98 2` and `52 50 48`
+        let table: MappingTable<u64> = [(98..100, 50), (50..98, 52)].into_iter().collect();
+        assert_eq!(table.map(79), 81);
+        assert_eq!(table.map(99), 51);
+        assert_eq!(table.map(10), 10);
+
+        assert_eq!(
+            table.split(40..99),
+            vec![(40..50, 40..50), (50..98, 52..100), (98..99, 50..51)]
+        );
+        assert_eq!(table.split(100..110), vec![(100..110, 100..110)]);
+        assert_eq!(table.split(5..5), vec![]);
+
+        let seeds: IntervalSet<u64> = [79..93, 55..68].into_iter().collect();
+        assert_eq!(table.map_set(&seeds).ranges(), &[57..70, 81..95]);
+
+        // every value is mapped the same way as by `map`.
+        let all: IntervalSet<u64> = std::iter::once(0..120).collect();
+        let mapped = table.map_set(&all);
+        assert!((0..120).all(|v| mapped.contains(table.map(v))));
+        assert_eq!(mapped.total_len(), 120);
+    }
+}
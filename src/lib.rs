@@ -1,3 +1,21 @@
+pub mod bitset;
+pub mod components;
+pub mod cycle;
+pub mod debug;
+pub mod equation;
+pub mod grid;
+pub mod interval;
+pub mod math;
+pub mod memo;
+pub mod order;
+pub mod parse;
+pub mod point;
+pub mod search;
 pub mod template;
+pub mod union_find;
+pub mod visualize;
+
+pub use grid::Grid;
+pub use point::{Direction, Point};
 
 // Use this file to add helper functions and additional modules.
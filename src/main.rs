@@ -7,7 +7,7 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::{commands::solve::VisualizeArgs, Day};
     use std::process;
 
     pub enum AppArguments {
@@ -21,12 +21,18 @@ mod args {
             day: Day,
             download: bool,
             overwrite: bool,
+            template: Option<String>,
+            return_type: Option<String>,
+            examples: Option<u8>,
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            inputs_dir: Option<String>,
+            debug: bool,
+            visualize: Option<VisualizeArgs>,
         },
         All {
             release: bool,
@@ -67,12 +73,26 @@ mod args {
                 day: args.free_from_str()?,
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
+                template: args.opt_value_from_str("--template")?,
+                return_type: args.opt_value_from_str("--return-type")?,
+                examples: args.opt_value_from_str("--examples")?,
             },
             Some("solve") => AppArguments::Solve {
                 day: args.free_from_str()?,
                 release: args.contains("--release"),
                 submit: args.opt_value_from_str("--submit")?,
                 dhat: args.contains("--dhat"),
+                inputs_dir: args.opt_value_from_str("--inputs-dir")?,
+                debug: args.contains("--debug"),
+                visualize: {
+                    let enabled = args.contains("--visualize");
+                    let options = VisualizeArgs {
+                        frame_delay: args.opt_value_from_str("--frame-delay")?,
+                        export: args.opt_value_from_str("--export")?,
+                        cell_size: args.opt_value_from_str("--cell-size")?,
+                    };
+                    enabled.then_some(options)
+                },
             },
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
@@ -110,8 +130,17 @@ fn main() {
                 day,
                 download,
                 overwrite,
+                template,
+                return_type,
+                examples,
             } => {
-                scaffold::handle(day, overwrite);
+                scaffold::handle(
+                    day,
+                    overwrite,
+                    template.as_deref(),
+                    return_type.as_deref(),
+                    examples,
+                );
                 if download {
                     download::handle(day);
                 }
@@ -121,12 +150,15 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                inputs_dir,
+                debug,
+                visualize,
+            } => solve::handle(day, release, dhat, submit, inputs_dir, debug, visualize),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
                     Some(day) => {
-                        scaffold::handle(day, false);
+                        scaffold::handle(day, false, None, None, None);
                         download::handle(day);
                         read::handle(day)
                     }
@@ -0,0 +1,335 @@
+//! Number theory and digit helpers. Everything that can overflow returns `None` instead of wrapping.
+
+/// Greatest common divisor. `gcd(0, 0)` is 0.
+#[must_use]
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, or `None` on overflow. `lcm(0, n)` is 0.
+#[must_use]
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Least common multiple of all values, e.g. the period after which several cycles align. `None` on overflow.
+pub fn lcm_all(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// Extended Euclidean algorithm: returns `(g, x, y)` with `a * x + b * y = g = gcd(a, b)` and `g >= 0`.
+///
+/// Returns `None` only if the gcd itself does not fit, i.e. when it is `2^63` for `i64::MIN` and 0 or `i64::MIN`.
+#[must_use]
+pub fn extended_gcd(a: i64, b: i64) -> Option<(i64, i64, i64)> {
+    let (g, x, y) = extended_gcd_wide(a.into(), b.into());
+    Some((g.try_into().ok()?, x.try_into().ok()?, y.try_into().ok()?))
+}
+
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a` reduced into `0..m`, also for negative `a`.
+///
+/// # Panics
+/// Panics if `m` is 0.
+#[must_use]
+pub fn modulo(a: i64, m: u64) -> u64 {
+    assert!(m > 0, "modulus must be positive");
+    i128::from(a).rem_euclid(i128::from(m)) as u64
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, or `None` if `a` and `m` are not coprime.
+///
+/// # Panics
+/// Panics if `m` is 0.
+#[must_use]
+pub fn mod_inverse(a: i64, m: u64) -> Option<u64> {
+    let a = modulo(a, m);
+    let (g, x, _) = extended_gcd_wide(a.into(), m.into());
+    (g == 1).then(|| x.rem_euclid(i128::from(m)) as u64)
+}
+
+/// `base^exp mod m` by repeated squaring. `0^0` is 1.
+///
+/// # Panics
+/// Panics if `m` is 0.
+#[must_use]
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    assert!(m > 0, "modulus must be positive");
+    let m = u128::from(m);
+    let mut base = u128::from(base) % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// Chinese remainder theorem: the smallest `x >= 0` with `x ≡ residue (mod modulus)` for every congruence,
+/// returned as `(x, lcm of the moduli)`. The moduli do not need to be coprime.
+///
+/// Returns `None` if the congruences contradict each other or the combined modulus overflows.
+///
+/// # Panics
+/// Panics if a modulus is 0.
+#[must_use]
+pub fn crt(congruences: &[(i64, u64)]) -> Option<(u64, u64)> {
+    let mut x: u128 = 0;
+    let mut m: u128 = 1;
+    for &(residue, modulus) in congruences {
+        let residue = u128::from(modulo(residue, modulus));
+        let modulus = u128::from(modulus);
+
+        // solve x + m * k ≡ residue (mod modulus) for k.
+        let (g, inverse, _) = extended_gcd_wide(m as i128, modulus as i128);
+        let g = g as u128;
+        let diff = (residue + modulus - x % modulus) % modulus;
+        if !diff.is_multiple_of(g) {
+            return None;
+        }
+        let step = modulus / g;
+        let inverse = inverse.rem_euclid(step as i128) as u128;
+        let k = (diff / g) % step * inverse % step;
+
+        x += m * k;
+        m = m.checked_mul(step).filter(|&m| m <= u128::from(u64::MAX))?;
+    }
+    Some((x as u64, m as u64))
+}
+
+/// The number of decimal digits, 1 for 0.
+#[must_use]
+pub fn digit_count(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// `10^exp`, or `None` on overflow.
+#[must_use]
+pub fn pow10(exp: u32) -> Option<u64> {
+    10_u64.checked_pow(exp)
+}
+
+/// Concatenates the decimal digits of `a` and `b`, e.g. `concat(12, 345) = 12345`. `None` on overflow.
+#[must_use]
+pub fn concat(a: u64, b: u64) -> Option<u64> {
+    if a == 0 {
+        return Some(b);
+    }
+    a.checked_mul(pow10(digit_count(b))?)?.checked_add(b)
+}
+
+/// Splits off the last `digit_count(suffix)` digits of `n` if they equal `suffix`, undoing [`concat`],
+/// e.g. `strip_suffix(12345, 45) = Some(123)`.
+#[must_use]
+pub fn strip_suffix(n: u64, suffix: u64) -> Option<u64> {
+    match pow10(digit_count(suffix)) {
+        Some(shift) => (n % shift == suffix).then_some(n / shift),
+        // a suffix with 20 digits can only be the whole number.
+        None => (n == suffix).then_some(0),
+    }
+}
+
+/// The largest `r` with `r * r <= n`.
+#[must_use]
+pub fn isqrt(n: u64) -> u64 {
+    n.isqrt()
+}
+
+#[must_use]
+pub fn is_square(n: u64) -> bool {
+    let r = isqrt(n);
+    r * r == n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A xorshift generator, so the property tests are reproducible without extra dependencies.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// Mostly small numbers, with some large ones and edge cases mixed in.
+        fn number(&mut self) -> u64 {
+            match self.next() % 8 {
+                0 => [0, 1, u64::MAX, u64::MAX - 1, 1 << 63][(self.next() % 5) as usize],
+                1 => self.next(),
+                _ => self.next() % 1000,
+            }
+        }
+    }
+
+    fn naive_gcd(a: u64, b: u64) -> u64 {
+        (1..=a.max(b))
+            .rev()
+            .find(|d| a.is_multiple_of(*d) && b.is_multiple_of(*d))
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        for a in 0..60 {
+            for b in 0..60 {
+                assert_eq!(gcd(a, b), naive_gcd(a, b), "gcd({a}, {b})");
+                let lcm = lcm(a, b).unwrap();
+                if a > 0 && b > 0 {
+                    assert_eq!(lcm, (1..).find(|m| m % a == 0 && m % b == 0).unwrap());
+                }
+            }
+        }
+
+        let mut rng = Rng(0x5eed);
+        for _ in 0..10_000 {
+            let (a, b) = (rng.number(), rng.number());
+            let g = gcd(a, b);
+            assert_eq!(g, gcd(b, a));
+            if g > 0 {
+                assert_eq!((a % g, b % g), (0, 0));
+                assert_eq!(gcd(a / g, b / g), 1);
+            }
+            let wide = u128::from(a) * u128::from(b) / u128::from(g.max(1));
+            assert_eq!(
+                lcm(a, b).map(u128::from),
+                u64::try_from(wide).ok().map(u128::from)
+            );
+        }
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([u64::MAX, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn extended_euclid_and_inverse() {
+        let mut rng = Rng(42);
+        for _ in 0..10_000 {
+            let (a, b) = (rng.number() as i64, rng.number() as i64);
+            let Some((g, x, y)) = extended_gcd(a, b) else {
+                assert_eq!(gcd(a.unsigned_abs(), b.unsigned_abs()), 1 << 63);
+                continue;
+            };
+            assert!(g >= 0);
+            assert_eq!(g as u64, gcd(a.unsigned_abs(), b.unsigned_abs()));
+            assert_eq!(
+                i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y),
+                i128::from(g)
+            );
+
+            let m = rng.number().max(1);
+            match mod_inverse(a, m) {
+                Some(inverse) => {
+                    assert!(inverse < m);
+                    let product = u128::from(modulo(a, m)) * u128::from(inverse);
+                    assert_eq!(product % u128::from(m), 1 % u128::from(m));
+                }
+                None => assert_ne!(gcd(modulo(a, m), m), 1),
+            }
+        }
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn modular_powers() {
+        let mut rng = Rng(7);
+        for _ in 0..1000 {
+            let (base, m) = (rng.number(), rng.number().max(1));
+            let exp = rng.next() % 50;
+            let mut expected = 1 % u128::from(m);
+            for _ in 0..exp {
+                expected = expected * u128::from(base) % u128::from(m);
+            }
+            assert_eq!(u128::from(mod_pow(base, exp, m)), expected);
+        }
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(0, 0, 7), 1);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(-1, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, u64::MAX), (0, u64::MAX - 1)]), None);
+
+        let mut rng = Rng(1234);
+        for _ in 0..2000 {
+            let congruences: Vec<(i64, u64)> = (0..1 + rng.next() % 3)
+                .map(|_| ((rng.next() % 40) as i64 - 20, 1 + rng.next() % 12))
+                .collect();
+            let naive = (0..lcm_all(congruences.iter().map(|&(_, m)| m)).unwrap())
+                .find(|&x| congruences.iter().all(|&(r, m)| x % m == modulo(r, m)));
+            assert_eq!(crt(&congruences).map(|(x, _)| x), naive, "{congruences:?}");
+        }
+    }
+
+    #[test]
+    fn digits() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(9), 1);
+        assert_eq!(digit_count(10), 2);
+        assert_eq!(digit_count(u64::MAX), 20);
+        assert_eq!(concat(12, 345), Some(12345));
+        assert_eq!(concat(12, 0), Some(120));
+        assert_eq!(concat(0, 7), Some(7));
+        assert_eq!(concat(u64::MAX / 10, 9), None);
+        assert_eq!(strip_suffix(12345, 45), Some(123));
+        assert_eq!(strip_suffix(12345, 44), None);
+        assert_eq!(strip_suffix(5, 5), Some(0));
+
+        let mut rng = Rng(99);
+        for _ in 0..10_000 {
+            let (a, b) = (rng.number(), rng.number());
+            assert_eq!(u64::from(digit_count(a)), a.to_string().len() as u64);
+            let expected = format!("{a}{b}").parse().ok();
+            assert_eq!(concat(a, b), expected, "concat({a}, {b})");
+            if let Some(n) = expected {
+                assert_eq!(strip_suffix(n, b), Some(a));
+            }
+        }
+    }
+
+    #[test]
+    fn square_roots() {
+        for n in 0..10_000 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n);
+            assert_eq!(is_square(n), r * r == n);
+        }
+        assert_eq!(isqrt(u64::MAX), u64::from(u32::MAX));
+        assert!(is_square(u64::from(u32::MAX).pow(2)));
+    }
+}
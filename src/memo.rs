@@ -0,0 +1,306 @@
+//! Memoisation for recursive solutions.
+//!
+//! A recursive helper becomes cached by taking a `&mut Memo` and wrapping its body:
+//!
+//! ```ignore
+//! use advent_of_code::memo::Memo;
+//!
+//! fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//!     memo.get_or_compute(n, |memo| {
+//!         if n < 2 {
+//!             n
+//!         } else {
+//!             fibonacci(memo, n - 1) + fibonacci(memo, n - 2)
+//!         }
+//!     })
+//! }
+//!
+//! assert_eq!(fibonacci(&mut Memo::new(), 90), 2_880_067_194_370_816_120);
+//! ```
+//!
+//! The [`memoize!`](crate::memoize) macro does the same without changing the signature.
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// A cache of computed values, optionally bounded to a number of entries.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    capacity: Option<usize>,
+    /// Insertion order of the keys, only tracked for bounded caches.
+    order: VecDeque<K>,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Memo<K, V> {
+    /// An unbounded cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            capacity: None,
+            order: VecDeque::new(),
+        }
+    }
+
+    /// A cache holding at most `capacity` entries, evicting the oldest entry when full.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0.
+    #[must_use]
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0, "a bounded cache needs room for one entry");
+        Self {
+            cache: HashMap::with_capacity(capacity),
+            capacity: Some(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the cached value for `key`, or computes, caches and returns it.
+    ///
+    /// `compute` gets the cache back so recursive calls can use it as well.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+        let value = compute(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        let Some(capacity) = self.capacity else {
+            self.cache.insert(key, value);
+            return;
+        };
+        if let Some(cached) = self.cache.get_mut(&key) {
+            *cached = value;
+            return;
+        }
+        while self.cache.len() >= capacity {
+            let oldest = self.order.pop_front().expect("every entry is tracked");
+            self.cache.remove(&oldest);
+        }
+        self.order.push_back(key.clone());
+        self.cache.insert(key, value);
+    }
+
+    /// The number of cached entries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+    }
+}
+
+thread_local! {
+    /// Empties the cache of a [`memoize!`](crate::memoize) function, one per function called on this thread.
+    static CLEARS: RefCell<Vec<fn()>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Empties the caches of all [`memoize!`](crate::memoize) functions called on the current thread.
+///
+/// The runner calls this between benchmark samples, so every sample computes its results again.
+pub fn clear_all() {
+    let clears = CLEARS.with(|clears| clears.borrow().clone());
+    for clear in clears {
+        clear();
+    }
+}
+
+#[doc(hidden)]
+pub fn register_clear(clear: fn()) {
+    CLEARS.with(|clears| clears.borrow_mut().push(clear));
+}
+
+/// Defines a function whose results are cached by its arguments, which have to be `Clone + Eq + Hash`.
+/// An optional `capacity = N;` prefix bounds the cache, see [`Memo::bounded`].
+///
+/// The cache is a thread local that lives as long as the thread, so it is shared between both parts.
+/// Pass a [`Memo`] explicitly where that matters. [`clear_all`] empties it, which `cargo time` does between
+/// benchmark samples.
+///
+/// ```ignore
+/// advent_of_code::memoize! {
+///     fn paths(row: u64, col: u64) -> u64 {
+///         if row == 0 || col == 0 {
+///             1
+///         } else {
+///             paths(row - 1, col) + paths(row, col - 1)
+///         }
+///     }
+/// }
+///
+/// assert_eq!(paths(16, 16), 601_080_390);
+/// ```
+///
+/// [`Memo`]: crate::memo::Memo
+/// [`Memo::bounded`]: crate::memo::Memo::bounded
+/// [`clear_all`]: crate::memo::clear_all
+#[macro_export]
+macro_rules! memoize {
+    (
+        capacity = $capacity:expr;
+        $(#[$attr:meta])* $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $body:block
+    ) => {
+        $crate::memoize!(@define ($crate::memo::Memo::bounded($capacity))
+            $(#[$attr])* $vis fn $name($($arg: $ty),*) -> $ret $body);
+    };
+    (
+        $(#[$attr:meta])* $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $body:block
+    ) => {
+        $crate::memoize!(@define ($crate::memo::Memo::new())
+            $(#[$attr])* $vis fn $name($($arg: $ty),*) -> $ret $body);
+    };
+    (
+        @define ($memo:expr)
+        $(#[$attr:meta])* $vis:vis fn $name:ident($($arg:ident: $ty:ty),*) -> $ret:ty $body:block
+    ) => {
+        $(#[$attr])* $vis fn $name($($arg: $ty),*) -> $ret {
+            ::std::thread_local! {
+                static CACHE: ::std::cell::RefCell<$crate::memo::Memo<($($ty,)*), $ret>> = {
+                    $crate::memo::register_clear(clear_cache);
+                    ::std::cell::RefCell::new($memo)
+                };
+            }
+            fn clear_cache() {
+                CACHE.with(|cache| cache.borrow_mut().clear());
+            }
+
+            let key = ($(::std::clone::Clone::clone(&$arg),)*);
+            if let Some(value) = CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+                return value;
+            }
+            fn compute($($arg: $ty),*) -> $ret $body
+
+            // the cache must not stay borrowed while the body recurses.
+            let value = compute($($arg),*);
+            CACHE.with(|cache| cache.borrow_mut().insert(key, ::std::clone::Clone::clone(&value)));
+            value
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collatz_steps(memo: &mut Memo<u64, u32>, n: u64, calls: &mut u32) -> u32 {
+        *calls += 1;
+        memo.get_or_compute(n, |memo| match n {
+            1 => 0,
+            n if n % 2 == 0 => 1 + collatz_steps(memo, n / 2, calls),
+            n => 1 + collatz_steps(memo, 3 * n + 1, calls),
+        })
+    }
+
+    #[test]
+    fn caches_recursive_calls() {
+        let mut memo = Memo::new();
+        let mut calls = 0;
+        assert_eq!(collatz_steps(&mut memo, 27, &mut calls), 111);
+        assert_eq!(calls, 112);
+
+        // 27 and everything on its way is cached now.
+        calls = 0;
+        assert_eq!(collatz_steps(&mut memo, 54, &mut calls), 112);
+        assert_eq!(calls, 2);
+        assert_eq!(memo.len(), 113);
+    }
+
+    #[test]
+    fn evicts_oldest_entries() {
+        let mut memo = Memo::bounded(2);
+        memo.insert(1, 'a');
+        memo.insert(2, 'b');
+        memo.insert(1, 'c');
+        memo.insert(3, 'd');
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.get(&1), None);
+        assert_eq!(memo.get(&2), Some(&'b'));
+        assert_eq!(memo.get_or_compute(3, |_| unreachable!()), 'd');
+
+        let mut calls = 0;
+        let mut memo = Memo::bounded(10);
+        assert_eq!(collatz_steps(&mut memo, 27, &mut calls), 111);
+        assert_eq!(memo.len(), 10);
+    }
+
+    crate::memoize! {
+        fn arrangements(stones: u64, blinks: u8) -> u64 {
+            if blinks == 0 {
+                return 1;
+            }
+            match stones {
+                0 => arrangements(1, blinks - 1),
+                n if crate::math::digit_count(n).is_multiple_of(2) => {
+                    let half = crate::math::pow10(crate::math::digit_count(n) / 2).unwrap();
+                    arrangements(n / half, blinks - 1) + arrangements(n % half, blinks - 1)
+                }
+                n => arrangements(n * 2024, blinks - 1),
+            }
+        }
+    }
+
+    crate::memoize! {
+        capacity = 16;
+        fn binomial(n: u64, k: u64) -> u64 {
+            if k == 0 || k == n {
+                1
+            } else {
+                binomial(n - 1, k - 1) + binomial(n - 1, k)
+            }
+        }
+    }
+
+    #[test]
+    fn memoizes_functions() {
+        assert_eq!(arrangements(125, 6) + arrangements(17, 6), 22);
+        assert_eq!(arrangements(125, 25) + arrangements(17, 25), 55312);
+        assert_eq!(binomial(30, 15), 155_117_520);
+    }
+
+    thread_local! {
+        static SQUARES: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+    }
+
+    crate::memoize! {
+        fn square(n: u64) -> u64 {
+            SQUARES.with(|calls| calls.set(calls.get() + 1));
+            n * n
+        }
+    }
+
+    #[test]
+    fn clears_memoized_functions() {
+        assert_eq!(square(3) + square(3), 18);
+        assert_eq!(SQUARES.with(std::cell::Cell::get), 1);
+
+        clear_all();
+        assert_eq!(square(3), 9);
+        assert_eq!(SQUARES.with(std::cell::Cell::get), 2);
+    }
+}
@@ -0,0 +1,407 @@
+//! Topological sorting and orderings built from pairwise "a before b" rules.
+//!
+//! Graphs are given as a list of nodes and a list of `(before, after)` edges. Nodes that only appear in an edge
+//! are added automatically. Results are deterministic: ties are broken by the order in which nodes first appear.
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+    error::Error,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use crate::bitset::BitGrid;
+
+/// Returned when the graph can not be sorted. `nodes` is one cycle, in edge order, e.g. `[a, b, c]` for
+/// `a -> b -> c -> a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    pub nodes: Vec<N>,
+}
+
+impl<N: Debug> Error for CycleError<N> {}
+
+impl<N: Debug> Display for CycleError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle detected:")?;
+        for node in self.nodes.iter().chain(self.nodes.first()) {
+            write!(f, " {node:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The graph with nodes replaced by their index of first appearance.
+struct Graph<N> {
+    nodes: Vec<N>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl<N: Copy + Eq + Hash> Graph<N> {
+    fn new(nodes: impl IntoIterator<Item = N>, edges: impl IntoIterator<Item = (N, N)>) -> Self {
+        let mut graph = Self {
+            nodes: vec![],
+            successors: vec![],
+        };
+        let mut indices = HashMap::new();
+        let mut index_of = |graph: &mut Self, node: N| {
+            *indices.entry(node).or_insert_with(|| {
+                graph.nodes.push(node);
+                graph.successors.push(vec![]);
+                graph.nodes.len() - 1
+            })
+        };
+
+        for node in nodes {
+            index_of(&mut graph, node);
+        }
+        for (before, after) in edges {
+            let before = index_of(&mut graph, before);
+            let after = index_of(&mut graph, after);
+            graph.successors[before].push(after);
+        }
+        graph
+    }
+
+    /// Depth-first search returning the nodes in reverse post-order, or the first cycle found.
+    fn dfs(&self) -> Result<Vec<N>, CycleError<N>> {
+        const UNVISITED: u8 = 0;
+        const ON_STACK: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![UNVISITED; self.nodes.len()];
+        let mut post_order = Vec::with_capacity(self.nodes.len());
+        // (node, index of the next successor to visit)
+        let mut stack: Vec<(usize, usize)> = vec![];
+
+        for root in 0..self.nodes.len() {
+            if state[root] != UNVISITED {
+                continue;
+            }
+            state[root] = ON_STACK;
+            stack.push((root, 0));
+
+            while let Some((node, next)) = stack.last_mut() {
+                let node = *node;
+                let Some(&successor) = self.successors[node].get(*next) else {
+                    state[node] = DONE;
+                    post_order.push(self.nodes[node]);
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+
+                match state[successor] {
+                    UNVISITED => {
+                        state[successor] = ON_STACK;
+                        stack.push((successor, 0));
+                    }
+                    ON_STACK => {
+                        let start = stack.iter().position(|&(n, _)| n == successor).unwrap();
+                        let nodes = stack[start..].iter().map(|&(n, _)| self.nodes[n]);
+                        return Err(CycleError {
+                            nodes: nodes.collect(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        post_order.reverse();
+        Ok(post_order)
+    }
+}
+
+/// Kahn's algorithm: repeatedly emits a node without remaining predecessors.
+///
+/// Whenever several nodes are ready, the one that appeared first is emitted, so an already sorted input is
+/// returned unchanged.
+pub fn kahn<N: Copy + Eq + Hash>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, CycleError<N>> {
+    let graph = Graph::new(nodes, edges);
+    let mut in_degree = vec![0_usize; graph.nodes.len()];
+    for &successor in graph.successors.iter().flatten() {
+        in_degree[successor] += 1;
+    }
+
+    // a min-heap of first-appearance indices, so the earliest ready node always comes next.
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..graph.nodes.len())
+        .filter(|&node| in_degree[node] == 0)
+        .map(Reverse)
+        .collect();
+    let mut sorted = Vec::with_capacity(graph.nodes.len());
+    while let Some(Reverse(node)) = ready.pop() {
+        sorted.push(graph.nodes[node]);
+        for &successor in &graph.successors[node] {
+            in_degree[successor] -= 1;
+            if in_degree[successor] == 0 {
+                ready.push(Reverse(successor));
+            }
+        }
+    }
+
+    if sorted.len() == graph.nodes.len() {
+        Ok(sorted)
+    } else {
+        // some nodes are stuck behind a cycle, let the DFS find it.
+        match graph.dfs() {
+            Err(cycle) => Err(cycle),
+            Ok(_) => unreachable!("Kahn's algorithm only stops early on cycles"),
+        }
+    }
+}
+
+/// Topological sort by depth-first search, using an explicit stack so deep graphs can not overflow.
+pub fn dfs<N: Copy + Eq + Hash>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Result<Vec<N>, CycleError<N>> {
+    Graph::new(nodes, edges).dfs()
+}
+
+/// Where [`Rules`] keeps its pairs.
+pub trait RuleStore<N>: Default {
+    fn insert(&mut self, a: N, b: N);
+    fn contains(&self, a: N, b: N) -> bool;
+}
+
+impl<N: Copy + Eq + Hash> RuleStore<N> for HashSet<(N, N)> {
+    fn insert(&mut self, a: N, b: N) {
+        HashSet::insert(self, (a, b));
+    }
+
+    fn contains(&self, a: N, b: N) -> bool {
+        HashSet::contains(self, &(a, b))
+    }
+}
+
+/// A bit matrix of rules between small integers, e.g. two-digit page numbers. Grows as rules are added.
+#[derive(Debug, Clone)]
+pub struct RuleMatrix {
+    /// The rule `a|b` is the position `(a, b)`.
+    bits: BitGrid,
+}
+
+impl Default for RuleMatrix {
+    fn default() -> Self {
+        Self {
+            bits: BitGrid::new(0, 0),
+        }
+    }
+}
+
+impl<N: Copy + Into<usize>> RuleStore<N> for RuleMatrix {
+    fn insert(&mut self, a: N, b: N) {
+        let (a, b) = (a.into(), b.into());
+        let size = a.max(b) + 1;
+        if size > self.bits.width() {
+            let size = size.next_power_of_two();
+            let mut bits = BitGrid::new(size, size);
+            for position in self.bits.iter() {
+                bits.insert(position);
+            }
+            self.bits = bits;
+        }
+        self.bits.insert((a, b));
+    }
+
+    fn contains(&self, a: N, b: N) -> bool {
+        self.bits.contains((a.into(), b.into()))
+    }
+}
+
+/// A set of pairwise "a before b" rules, e.g. `47|53` page ordering rules.
+///
+/// Only the given pairs are known, rules are not applied transitively. [`Rules::compare`] is therefore only
+/// a valid ordering for `sort_by` if every pair of sorted elements has a rule, or the rules are transitive.
+///
+/// Rules are hashed by default, [`DenseRules`] keeps them in a [`RuleMatrix`] instead.
+#[derive(Debug, Clone)]
+pub struct Rules<N, S = HashSet<(N, N)>> {
+    before: S,
+    nodes: PhantomData<N>,
+}
+
+/// Rules between small integers, looked up in a bit matrix.
+pub type DenseRules<N> = Rules<N, RuleMatrix>;
+
+impl<N: Copy + Eq + Hash, S: RuleStore<N>> Default for Rules<N, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Copy + Eq + Hash, S: RuleStore<N>> FromIterator<(N, N)> for Rules<N, S> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(iter: I) -> Self {
+        let mut rules = Self::new();
+        for (a, b) in iter {
+            rules.insert(a, b);
+        }
+        rules
+    }
+}
+
+impl<N: Copy + Eq + Hash, S: RuleStore<N>> Rules<N, S> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            before: S::default(),
+            nodes: PhantomData,
+        }
+    }
+
+    /// Adds the rule that `a` comes before `b`.
+    pub fn insert(&mut self, a: N, b: N) {
+        self.before.insert(a, b);
+    }
+
+    #[must_use]
+    pub fn is_before(&self, a: N, b: N) -> bool {
+        self.before.contains(a, b)
+    }
+
+    /// `Less` if `a` must come before `b`, `Greater` if `b` must come before `a`, `Equal` if no rule applies.
+    #[must_use]
+    pub fn compare(&self, a: &N, b: &N) -> Ordering {
+        if self.is_before(*a, *b) {
+            Ordering::Less
+        } else if self.is_before(*b, *a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// A comparator for `sort_by`, see [`Rules::compare`].
+    pub fn comparator(&self) -> impl Fn(&N, &N) -> Ordering + '_ {
+        |a, b| self.compare(a, b)
+    }
+
+    /// Whether no rule is violated by `items`, checking every pair.
+    #[must_use]
+    pub fn is_ordered(&self, items: &[N]) -> bool {
+        items
+            .iter()
+            .enumerate()
+            .all(|(i, &b)| items[..i].iter().all(|&a| !self.is_before(b, a)))
+    }
+
+    /// Sorts `items` so that all rules between them hold, without requiring the rules to be transitive.
+    ///
+    /// The result is a permutation of `items`: repeated items are kept and end up next to each other.
+    pub fn sort(&self, items: &[N]) -> Result<Vec<N>, CycleError<N>> {
+        let mut counts: HashMap<N, usize> = HashMap::new();
+        let mut distinct = vec![];
+        for &item in items {
+            *counts.entry(item).or_insert_with(|| {
+                distinct.push(item);
+                0
+            }) += 1;
+        }
+
+        let edges = distinct.iter().flat_map(|&a| {
+            distinct
+                .iter()
+                .filter(move |&&b| self.is_before(a, b))
+                .map(move |&b| (a, b))
+        });
+        let sorted = kahn(distinct.iter().copied(), edges)?;
+        Ok(sorted
+            .into_iter()
+            .flat_map(|item| std::iter::repeat_n(item, counts[&item]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether every edge points forward in `sorted`.
+    fn respects(sorted: &[u8], edges: &[(u8, u8)]) -> bool {
+        let position = |n| sorted.iter().position(|&m| m == n).unwrap();
+        edges.iter().all(|&(a, b)| position(a) < position(b))
+    }
+
+    #[test]
+    fn sorts_dags() {
+        let edges = [
+            (5, 11),
+            (7, 11),
+            (7, 8),
+            (3, 8),
+            (3, 10),
+            (11, 2),
+            (11, 9),
+            (11, 10),
+            (8, 9),
+        ];
+        let nodes = [2, 3, 5, 7, 8, 9, 10, 11];
+
+        for sorted in [kahn(nodes, edges).unwrap(), dfs(nodes, edges).unwrap()] {
+            assert_eq!(sorted.len(), nodes.len());
+            assert!(respects(&sorted, &edges), "{sorted:?}");
+        }
+        assert_eq!(kahn([1, 2, 3], []).unwrap(), vec![1, 2, 3]);
+        assert_eq!(kahn([], [(1, 2)]).unwrap(), vec![1, 2]);
+        // c is ready from the start, but b appeared first and becomes ready before c is emitted.
+        assert_eq!(
+            kahn(['a', 'b', 'c'], [('a', 'b')]).unwrap(),
+            vec!['a', 'b', 'c']
+        );
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let edges = [(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)];
+        let expected = CycleError {
+            nodes: vec![1, 2, 3],
+        };
+        assert_eq!(kahn([0, 4], edges), Err(expected.clone()));
+        assert_eq!(dfs([0, 4], edges), Err(expected.clone()));
+        assert_eq!(expected.to_string(), "cycle detected: 1 2 3 1");
+        assert_eq!(dfs([1], [(1, 1)]), Err(CycleError { nodes: vec![1] }));
+    }
+
+    #[test]
+    fn orders_by_rules() {
+        let rules: Rules<u8> = [
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (97, 47),
+            (75, 29),
+            (61, 13),
+            (75, 53),
+        ]
+        .into_iter()
+        .collect();
+
+        assert!(rules.is_ordered(&[75, 97, 47, 61, 53]));
+        assert!(!rules.is_ordered(&[61, 13, 97]));
+
+        let mut update = vec![61, 13, 97];
+        update.sort_by(rules.comparator());
+        assert_eq!(update, vec![97, 61, 13]);
+        assert_eq!(rules.sort(&[61, 13, 97]).unwrap(), vec![97, 61, 13]);
+        // 5 has no rules, but every item that appeared before it comes first once it is ready.
+        assert_eq!(
+            rules.sort(&[13, 61, 97, 13, 5]).unwrap(),
+            vec![97, 61, 13, 13, 5]
+        );
+
+        assert_eq!(rules.compare(&1, &2), Ordering::Equal);
+
+        let dense: DenseRules<u8> = rules.before.iter().copied().collect();
+        assert!(dense.is_before(97, 13) && !dense.is_before(13, 97));
+        assert!(!dense.is_before(200, 13));
+        assert_eq!(
+            dense.sort(&[13, 61, 97, 13, 5]).unwrap(),
+            vec![97, 61, 13, 13, 5]
+        );
+    }
+}
@@ -0,0 +1,300 @@
+//! Helpers for parsing puzzle inputs.
+//!
+//! Parsers that can fail return a [`ParseError`] pointing at the offending line, so solutions can return
+//! `Result` and have the runner print a useful message instead of panicking on an `unwrap`.
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use crate::grid::{Grid, GridError};
+
+/// An error which can be returned by the parsers of this module. Line numbers start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn integers<T: FromStr>(text: &str, signed: bool) -> impl Iterator<Item = T> + '_ {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() && !bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return None;
+        }
+
+        let start = if signed && i > 0 && bytes[i - 1] == b'-' {
+            i - 1
+        } else {
+            i
+        };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let number = &text[start..i];
+        let Ok(value) = number.parse() else {
+            panic!("number \"{number}\" does not fit into the target type");
+        };
+        Some(value)
+    })
+}
+
+/// Extracts every run of digits from arbitrary text, e.g. `"x=12, y=-3"` yields `12, 3`.
+///
+/// # Panics
+/// Panics if a number does not fit into `T`.
+pub fn unsigned<T: FromStr>(text: &str) -> impl Iterator<Item = T> + '_ {
+    integers(text, false)
+}
+
+/// Extracts every integer from arbitrary text, treating a `-` directly before a number as its sign,
+/// e.g. `"x=12, y=-3"` yields `12, -3`.
+///
+/// # Panics
+/// Panics if a number does not fit into `T`.
+pub fn signed<T: FromStr>(text: &str) -> impl Iterator<Item = T> + '_ {
+    integers(text, true)
+}
+
+/// Splits the input into blocks separated by blank lines. Handles `\r\n` line endings,
+/// whitespace-only separator lines and repeated separators.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    numbered_blocks(input).map(|(_, block)| block)
+}
+
+/// Like [`blocks`], also yielding the line number of each block's first line.
+pub fn numbered_blocks(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut blocks = vec![];
+    let mut start: Option<(usize, usize)> = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        if line.trim().is_empty() {
+            if let Some((line_number, block_start)) = start.take() {
+                blocks.push((line_number, &input[block_start..end]));
+            }
+        } else {
+            start.get_or_insert((i + 1, offset));
+            end = offset + line.trim_end_matches(['\n', '\r']).len();
+        }
+        offset += line.len();
+    }
+    if let Some((line_number, block_start)) = start {
+        blocks.push((line_number, &input[block_start..end]));
+    }
+
+    blocks.into_iter()
+}
+
+/// Parses every line with `f`. Errors are annotated with the line number.
+pub fn lines<T, E: Display>(
+    text: &str,
+    f: impl FnMut(&str) -> Result<T, E>,
+) -> Result<Vec<T>, ParseError> {
+    lines_from(1, text, f)
+}
+
+/// Like [`lines`], for text that starts at line `first_line` of the input, e.g. a block of [`numbered_blocks`].
+pub fn lines_from<T, E: Display>(
+    first_line: usize,
+    text: &str,
+    mut f: impl FnMut(&str) -> Result<T, E>,
+) -> Result<Vec<T>, ParseError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| ParseError::new(first_line + i, e.to_string())))
+        .collect()
+}
+
+fn parse_value<T: FromStr>(value: &str) -> Result<T, String> {
+    let value = value.trim();
+    value
+        .parse()
+        .map_err(|_| format!("could not parse \"{value}\""))
+}
+
+/// Parses a line of two values around a separator, e.g. `pair::<u8, u8>("47|53", "|")`.
+pub fn pair<A: FromStr, B: FromStr>(line: &str, separator: &str) -> Result<(A, B), String> {
+    let (a, b) = line
+        .split_once(separator)
+        .ok_or_else(|| format!("expected \"{separator}\" in \"{line}\""))?;
+    Ok((parse_value(a)?, parse_value(b)?))
+}
+
+/// Parses a list of values, e.g. `list::<u8>("75,47,61", ",")`. An empty separator splits on whitespace.
+pub fn list<T: FromStr>(line: &str, separator: &str) -> Result<Vec<T>, String> {
+    if separator.is_empty() {
+        line.split_whitespace().map(parse_value).collect()
+    } else {
+        line.split(separator).map(parse_value).collect()
+    }
+}
+
+/// Parses a list of exactly `N` values, see [`list`].
+pub fn array<T: FromStr, const N: usize>(line: &str, separator: &str) -> Result<[T; N], String> {
+    let values: Vec<T> = list(line, separator)?;
+    let len = values.len();
+    values
+        .try_into()
+        .map_err(|_| format!("expected {N} values, found {len} in \"{line}\""))
+}
+
+/// Parses a grid of bytes, see [`Grid::parse_with`].
+pub fn grid(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse_with(input, |byte| byte).map_err(grid_error)
+}
+
+/// Parses a grid of single digits.
+pub fn digit_grid(input: &str) -> Result<Grid<u8>, ParseError> {
+    grid_with(input, |byte| byte.is_ascii_digit().then_some(byte - b'0'))
+}
+
+/// Parses a grid, converting every byte with `f`. Bytes for which `f` returns `None` are reported as errors.
+pub fn grid_with<T>(input: &str, f: impl Fn(u8) -> Option<T>) -> Result<Grid<T>, ParseError> {
+    let grid = grid(input)?;
+    let cells = grid
+        .cells()
+        .iter()
+        .enumerate()
+        .map(|(i, &byte)| {
+            f(byte).ok_or_else(|| {
+                let (row, col) = grid.position_of(i);
+                ParseError::new(
+                    row + 1,
+                    format!(
+                        "unexpected character '{}' in column {}",
+                        char::from(byte),
+                        col + 1
+                    ),
+                )
+            })
+        })
+        .collect::<Result<Vec<T>, ParseError>>()?;
+    Grid::from_vec(grid.width(), grid.height(), cells).map_err(grid_error)
+}
+
+fn grid_error(e: GridError) -> ParseError {
+    match e {
+        GridError::RaggedLine { line, .. } => ParseError::new(line, e.to_string()),
+        GridError::SizeMismatch { .. } => ParseError::new(1, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_integers() {
+        let text = "p=0,4 v=-3,-12 x-y 007";
+        assert_eq!(
+            signed::<i32>(text).collect::<Vec<_>>(),
+            vec![0, 4, -3, -12, 7]
+        );
+        assert_eq!(
+            unsigned::<u32>(text).collect::<Vec<_>>(),
+            vec![0, 4, 3, 12, 7]
+        );
+        assert_eq!(signed::<i64>("").count(), 0);
+        assert_eq!(
+            unsigned::<u64>("190: 10 19").collect::<Vec<_>>(),
+            vec![190, 10, 19]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "\"300\" does not fit")]
+    fn panics_on_overflow() {
+        let _ = unsigned::<u8>("1 300").collect::<Vec<_>>();
+    }
+
+    #[test]
+    fn splits_blocks() {
+        let input = "a\r\nb\r\n\r\nc\n  \n\n\nd\ne\n";
+        assert_eq!(
+            blocks(input).collect::<Vec<_>>(),
+            vec!["a\r\nb", "c", "d\ne"]
+        );
+        assert_eq!(
+            numbered_blocks(input)
+                .map(|(line, _)| line)
+                .collect::<Vec<_>>(),
+            vec![1, 4, 8]
+        );
+        assert_eq!(blocks("\n\na").collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(blocks("").count(), 0);
+    }
+
+    #[test]
+    fn parses_fixed_shapes() {
+        assert_eq!(pair::<u8, u8>("47|53", "|"), Ok((47, 53)));
+        assert_eq!(
+            pair::<u64, String>("190: 10 19", ":"),
+            Ok((190, "10 19".to_string()))
+        );
+        assert!(pair::<u8, u8>("47-53", "|").is_err());
+        assert_eq!(list::<u8>("75,47,61", ","), Ok(vec![75, 47, 61]));
+        assert_eq!(list::<i32>(" 1  -2 3 ", ""), Ok(vec![1, -2, 3]));
+        assert_eq!(array::<u8, 2>("3   4", ""), Ok([3, 4]));
+        assert_eq!(
+            array::<u8, 2>("3 4 5", ""),
+            Err("expected 2 values, found 3 in \"3 4 5\"".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_line_numbers() {
+        let input = "1|2\n3|4\n\n1,2\n1,x\n";
+        let mut blocks = numbered_blocks(input);
+
+        let (first_line, rules) = blocks.next().unwrap();
+        assert_eq!(
+            lines_from(first_line, rules, |line| pair::<u8, u8>(line, "|")),
+            Ok(vec![(1, 2), (3, 4)])
+        );
+
+        let (first_line, updates) = blocks.next().unwrap();
+        let error = lines_from(first_line, updates, |line| list::<u8>(line, ",")).unwrap_err();
+        assert_eq!(error, ParseError::new(5, "could not parse \"x\""));
+        assert_eq!(error.to_string(), "line 5: could not parse \"x\"");
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = grid("ab\ncd").unwrap();
+        assert_eq!(grid[(1, 0)], b'c');
+        assert_eq!(super::grid("ab\nc").unwrap_err().line, 2);
+
+        let digits = digit_grid("01\n29").unwrap();
+        assert_eq!(digits.cells(), &[0, 1, 2, 9]);
+        assert_eq!(
+            digit_grid("01\n2x").unwrap_err().to_string(),
+            "line 2: unexpected character 'x' in column 2"
+        );
+    }
+}
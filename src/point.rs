@@ -0,0 +1,457 @@
+//! Points in a two-dimensional plane and the directions to move between them.
+//!
+//! Points use screen coordinates: `x` grows to the right and `y` grows downwards, matching the `(row, col)`
+//! positions of [`Grid`](crate::Grid) with `row = y` and `col = x`.
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+/// Integer types that can be used as coordinates of a [`Point`].
+pub trait Coord:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Returns `|self - other|`.
+    #[must_use]
+    fn abs_diff(self, other: Self) -> Self;
+
+    fn to_usize(self) -> Option<usize>;
+
+    fn from_usize(value: usize) -> Option<Self>;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs_diff(self, other: Self) -> Self {
+                    self.max(other) - self.min(other)
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+
+                fn from_usize(value: usize) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Coord> Point<T> {
+    pub const ORIGIN: Self = Self::new(T::ZERO, T::ZERO);
+
+    /// Sum of the absolute differences of both coordinates, i.e. the number of orthogonal steps between two points.
+    #[must_use]
+    pub fn manhattan(self, other: Self) -> T {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Maximum of the absolute differences of both coordinates, i.e. the number of king moves between two points.
+    #[must_use]
+    pub fn chebyshev(self, other: Self) -> T {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Converts the point into a `(row, col)` grid position, if both coordinates are non-negative.
+    #[must_use]
+    pub fn to_grid(self) -> Option<(usize, usize)> {
+        Some((self.y.to_usize()?, self.x.to_usize()?))
+    }
+
+    /// Creates a point from a `(row, col)` grid position, if it fits into the coordinate type.
+    #[must_use]
+    pub fn from_grid((row, col): (usize, usize)) -> Option<Self> {
+        Some(Self::new(T::from_usize(col)?, T::from_usize(row)?))
+    }
+}
+
+impl<T: Coord + Neg<Output = T>> Point<T> {
+    /// Rotates the point by 90 degrees clockwise around the origin.
+    #[must_use]
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates the point by 90 degrees counter-clockwise around the origin.
+    #[must_use]
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Rotates the point around another point by `quarter_turns` of 90 degrees clockwise.
+    /// Negative values rotate counter-clockwise.
+    #[must_use]
+    pub fn rotate_around(self, center: Self, quarter_turns: i32) -> Self {
+        let relative = self - center;
+        let rotated = match quarter_turns.rem_euclid(4) {
+            0 => relative,
+            1 => relative.rotate_right(),
+            2 => -relative,
+            _ => relative.rotate_left(),
+        };
+        rotated + center
+    }
+
+    /// Returns the point one step away in a direction.
+    #[must_use]
+    pub fn step(self, direction: Direction) -> Self
+    where
+        T: From<i8>,
+    {
+        self + direction.offset()
+    }
+
+    /// Iterates the four orthogonal neighbours, clockwise starting at the top.
+    pub fn neighbours4(self) -> impl Iterator<Item = Self>
+    where
+        T: From<i8>,
+    {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .map(move |direction| self.step(direction))
+    }
+
+    /// Iterates all eight neighbours, clockwise starting at the top.
+    pub fn neighbours8(self) -> impl Iterator<Item = Self>
+    where
+        T: From<i8>,
+    {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self.step(direction))
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(point: Point<T>) -> Self {
+        (point.x, point.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self {
+        Self::new(self.x * scalar, self.y * scalar)
+    }
+}
+
+impl<T: Div<Output = T> + Copy> Div<T> for Point<T> {
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self {
+        Self::new(self.x / scalar, self.y / scalar)
+    }
+}
+
+impl<T: Rem<Output = T> + Copy> Rem<T> for Point<T> {
+    type Output = Self;
+
+    fn rem(self, scalar: T) -> Self {
+        Self::new(self.x % scalar, self.y % scalar)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: MulAssign + Copy> MulAssign<T> for Point<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        self.x *= scalar;
+        self.y *= scalar;
+    }
+}
+
+impl<T: DivAssign + Copy> DivAssign<T> for Point<T> {
+    fn div_assign(&mut self, scalar: T) {
+        self.x /= scalar;
+        self.y /= scalar;
+    }
+}
+
+impl<T: Add<Output = T> + From<i8>> Add<Direction> for Point<T> {
+    type Output = Self;
+
+    fn add(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+}
+
+impl<T: AddAssign + From<i8>> AddAssign<Direction> for Point<T> {
+    fn add_assign(&mut self, direction: Direction) {
+        *self += direction.offset();
+    }
+}
+
+/// A compass direction on screen, `Up` being towards smaller `y` values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    #[default]
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// All eight directions, clockwise starting at `Up`.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// The four orthogonal directions, clockwise starting at `Up`.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The four diagonal directions, clockwise starting at `UpRight`.
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+    ];
+
+    /// Position in [`Direction::ALL`], i.e. the number of 45 degree turns clockwise from `Up`.
+    #[must_use]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    #[must_use]
+    const fn rotate(self, eighth_turns: usize) -> Self {
+        Self::ALL[(self.index() + eighth_turns) % 8]
+    }
+
+    /// Turns by 90 degrees clockwise.
+    #[must_use]
+    pub const fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Turns by 90 degrees counter-clockwise.
+    #[must_use]
+    pub const fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Turns by 45 degrees clockwise.
+    #[must_use]
+    pub const fn turn_right_45(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Turns by 45 degrees counter-clockwise.
+    #[must_use]
+    pub const fn turn_left_45(self) -> Self {
+        self.rotate(7)
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    #[must_use]
+    pub const fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// Returns the `(d_row, d_col)` offset of a single step, as used by [`Grid`](crate::Grid).
+    #[must_use]
+    pub const fn grid_offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::UpRight => (-1, 1),
+            Direction::Right => (0, 1),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (1, 0),
+            Direction::DownLeft => (1, -1),
+            Direction::Left => (0, -1),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    /// Returns the offset of a single step as a point.
+    #[must_use]
+    pub fn offset<T: From<i8>>(self) -> Point<T> {
+        let (d_row, d_col) = self.grid_offset();
+        #[allow(clippy::cast_possible_truncation)]
+        Point::new(T::from(d_col as i8), T::from(d_row as i8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supports_arithmetic() {
+        let mut p = Point::new(3, -2);
+        assert_eq!(p + Point::new(1, 1), Point::new(4, -1));
+        assert_eq!(p - Point::new(1, 1), Point::new(2, -3));
+        assert_eq!(-p, Point::new(-3, 2));
+        assert_eq!(p * 3, Point::new(9, -6));
+        assert_eq!(p / 2, Point::new(1, -1));
+        assert_eq!(p % 2, Point::new(1, 0));
+
+        p += Point::new(1, 2);
+        assert_eq!(p, Point::new(4, 0));
+        p -= Point::new(4, 1);
+        assert_eq!(p, Point::new(0, -1));
+        p *= 5;
+        assert_eq!(p, Point::new(0, -5));
+        p /= 5;
+        assert_eq!(p, Point::new(0, -1));
+        p += Direction::Right;
+        assert_eq!(p, Point::new(1, -1));
+    }
+
+    #[test]
+    fn measures_distances() {
+        let a = Point::new(1_i64, 5);
+        let b = Point::new(-2, 1);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(Point::new(2_u32, 9).manhattan(Point::new(5, 3)), 9);
+    }
+
+    #[test]
+    fn rotates_points() {
+        let p = Point::new(2, -1);
+        assert_eq!(p.rotate_right(), Point::new(1, 2));
+        assert_eq!(p.rotate_left(), Point::new(-1, -2));
+        assert_eq!(p.rotate_right().rotate_left(), p);
+        assert_eq!(
+            Point::new(3, 1).rotate_around(Point::new(1, 1), 1),
+            Point::new(1, 3)
+        );
+        assert_eq!(
+            Point::new(3, 1).rotate_around(Point::new(1, 1), -2),
+            Point::new(-1, 1)
+        );
+        assert_eq!(
+            Direction::Up.offset::<i32>().rotate_right(),
+            Direction::Right.offset()
+        );
+    }
+
+    #[test]
+    fn turns_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::UpLeft.turn_right_45(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left_45(), Direction::UpLeft);
+        assert_eq!(Direction::DownRight.opposite(), Direction::UpLeft);
+        assert!(Direction::DIAGONAL.iter().all(|d| d.is_diagonal()));
+        assert!(!Direction::ORTHOGONAL.iter().any(|d| d.is_diagonal()));
+    }
+
+    #[test]
+    fn converts_to_grid_positions() {
+        assert_eq!(Point::new(3, 1).to_grid(), Some((1, 3)));
+        assert_eq!(Point::new(-1, 1).to_grid(), None);
+        assert_eq!(Point::<i32>::from_grid((1, 3)), Some(Point::new(3, 1)));
+        assert_eq!(Point::<i8>::from_grid((1, 300)), None);
+        assert_eq!(Direction::Up.offset::<i32>(), Point::new(0, -1));
+        assert_eq!(Direction::DownLeft.grid_offset(), (1, -1));
+    }
+
+    #[test]
+    fn iterates_neighbours() {
+        let p = Point::new(0, 0);
+        assert_eq!(
+            p.neighbours4().collect::<Vec<_>>(),
+            vec![
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0)
+            ]
+        );
+        assert_eq!(p.neighbours8().count(), 8);
+        assert!(p.neighbours8().all(|n| n.chebyshev(p) == 1));
+    }
+}
@@ -0,0 +1,562 @@
+//! Graph searches over implicit graphs.
+//!
+//! Graphs are described by a successor function: for BFS it returns the neighbouring nodes,
+//! for Dijkstra and A* it returns `(node, cost)` pairs. Costs start at `C::default()`, i.e. zero for numbers.
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Types that can be used as costs of an edge.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T>> Cost for T {}
+
+/// The distances of all reachable nodes from the start node(s), along with one shortest path to each of them.
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    pub distances: HashMap<N, C>,
+    parents: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    #[must_use]
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// Reconstructs a shortest path from a start node to `goal`, both included.
+    #[must_use]
+    pub fn path_to(&self, goal: &N) -> Option<Vec<N>> {
+        self.distances.get(goal)?;
+        Some(reconstruct_path(&self.parents, goal.clone()))
+    }
+}
+
+fn reconstruct_path<N: Clone + Eq + Hash>(parents: &HashMap<N, N>, goal: N) -> Vec<N> {
+    let mut path = vec![goal];
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth-first search from a single node. Distances are the number of steps.
+pub fn bfs<N, I>(start: N, successors: impl FnMut(&N) -> I) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    bfs_multi([start], successors)
+}
+
+/// Breadth-first search from several nodes at once. Every node's distance is the one to its closest start.
+pub fn bfs_multi<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if distances.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node] + 1;
+        for next in successors(&node) {
+            if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+                entry.insert(distance);
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Search { distances, parents }
+}
+
+/// Breadth-first search that stops at the first node matching `is_goal`, returning the path to it.
+pub fn bfs_path<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents: HashMap<N, N> = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(reconstruct_path(&parents, node));
+        }
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// An entry of the priority queue, ordered so that `BinaryHeap` pops the lowest priority first.
+struct State<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for State<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for State<N, C> {}
+
+impl<N, C: Ord> PartialOrd for State<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for State<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Dijkstra's algorithm from a single node.
+pub fn dijkstra<N, C, I>(start: N, successors: impl FnMut(&N) -> I) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    dijkstra_multi([start], successors)
+}
+
+/// Dijkstra's algorithm from several nodes at once. Every node's distance is the one to its closest start.
+pub fn dijkstra_multi<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut distances: HashMap<N, C> = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        distances.insert(start.clone(), C::default());
+        heap.push(State {
+            priority: C::default(),
+            cost: C::default(),
+            node: start,
+        });
+    }
+
+    while let Some(State { cost, node, .. }) = heap.pop() {
+        if distances.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        for (next, edge) in successors(&node) {
+            let next_cost = cost + edge;
+            if distances.get(&next).is_none_or(|&best| next_cost < best) {
+                distances.insert(next.clone(), next_cost);
+                parents.insert(next.clone(), node.clone());
+                heap.push(State {
+                    priority: next_cost,
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    Search { distances, parents }
+}
+
+/// Dijkstra's algorithm that stops at the first node matching `is_goal`, returning the path to it and its cost.
+pub fn dijkstra_path<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search, returning a cheapest path to the first node matching `is_goal` and its cost.
+///
+/// The heuristic must never overestimate the remaining cost to a goal, otherwise the path may not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut distances: HashMap<N, C> = HashMap::from([(start.clone(), C::default())]);
+    let mut parents = HashMap::new();
+    let mut heap = BinaryHeap::from([State {
+        priority: heuristic(&start),
+        cost: C::default(),
+        node: start,
+    }]);
+
+    while let Some(State { cost, node, .. }) = heap.pop() {
+        if distances.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        if is_goal(&node) {
+            return Some((reconstruct_path(&parents, node), cost));
+        }
+        for (next, edge) in successors(&node) {
+            let next_cost = cost + edge;
+            if distances.get(&next).is_none_or(|&best| next_cost < best) {
+                distances.insert(next.clone(), next_cost);
+                parents.insert(next.clone(), node.clone());
+                heap.push(State {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// The distances of all reachable nodes from the start node(s), along with every shortest path to each of them.
+#[derive(Debug, Clone)]
+pub struct AllShortestPaths<N, C> {
+    pub distances: HashMap<N, C>,
+    parents: HashMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> AllShortestPaths<N, C> {
+    #[must_use]
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// Returns every node that lies on at least one shortest path to `goal`, including the start and `goal`.
+    #[must_use]
+    pub fn nodes_on_paths(&self, goal: &N) -> HashSet<N> {
+        let mut nodes = HashSet::new();
+        if !self.distances.contains_key(goal) {
+            return nodes;
+        }
+
+        let mut stack = vec![goal.clone()];
+        while let Some(node) = stack.pop() {
+            if nodes.insert(node.clone()) {
+                stack.extend(self.parents.get(&node).into_iter().flatten().cloned());
+            }
+        }
+        nodes
+    }
+
+    /// Enumerates every shortest path to `goal`. The number of paths can grow exponentially.
+    #[must_use]
+    pub fn paths_to(&self, goal: &N) -> Vec<Vec<N>> {
+        if !self.distances.contains_key(goal) {
+            return vec![];
+        }
+
+        let mut paths = vec![];
+        let mut stack = vec![vec![goal.clone()]];
+        while let Some(path) = stack.pop() {
+            match self.parents.get(path.last().unwrap()) {
+                Some(parents) if !parents.is_empty() => {
+                    for parent in parents {
+                        let mut next = path.clone();
+                        next.push(parent.clone());
+                        stack.push(next);
+                    }
+                }
+                _ => {
+                    let mut path = path;
+                    path.reverse();
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// Dijkstra's algorithm that keeps track of all shortest paths instead of a single one.
+pub fn dijkstra_all<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> AllShortestPaths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut distances: HashMap<N, C> = HashMap::new();
+    let mut parents: HashMap<N, Vec<N>> = HashMap::new();
+    let mut settled: HashSet<N> = HashSet::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        distances.insert(start.clone(), C::default());
+        heap.push(State {
+            priority: C::default(),
+            cost: C::default(),
+            node: start,
+        });
+    }
+
+    while let Some(State { cost, node, .. }) = heap.pop() {
+        if !settled.insert(node.clone()) {
+            continue;
+        }
+        for (next, edge) in successors(&node) {
+            let next_cost = cost + edge;
+            match distances.get(&next).map(|best| next_cost.cmp(best)) {
+                Some(Ordering::Greater) => {}
+                // only a zero-cost edge reaches a settled node at equal cost. If that node is an ancestor,
+                // recording it as a parent would make the parents cyclic.
+                Some(Ordering::Equal)
+                    if settled.contains(&next) && has_ancestor(&parents, &node, &next) => {}
+                Some(Ordering::Equal) => {
+                    parents.entry(next).or_default().push(node.clone());
+                }
+                Some(Ordering::Less) | None => {
+                    distances.insert(next.clone(), next_cost);
+                    parents.insert(next.clone(), vec![node.clone()]);
+                    heap.push(State {
+                        priority: next_cost,
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+    }
+
+    AllShortestPaths { distances, parents }
+}
+
+/// Whether `ancestor` is `node` itself or can be reached by following the parents of `node`.
+fn has_ancestor<N: Clone + Eq + Hash>(
+    parents: &HashMap<N, Vec<N>>,
+    node: &N,
+    ancestor: &N,
+) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack = vec![node.clone()];
+    while let Some(node) = stack.pop() {
+        if &node == ancestor {
+            return true;
+        }
+        if seen.insert(node.clone()) {
+            stack.extend(parents.get(&node).into_iter().flatten().cloned());
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    /// ```text
+    /// a --1-- b --1-- d
+    ///  \      |      /
+    ///   4     2     1
+    ///    \    |    /
+    ///     `-- c --`      e (unreachable)
+    /// ```
+    fn weighted(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 4)],
+            'b' => vec![('a', 1), ('c', 2), ('d', 1)],
+            'c' => vec![('a', 4), ('b', 2), ('d', 1)],
+            'd' => vec![('b', 1), ('c', 1)],
+            _ => vec![],
+        }
+    }
+
+    fn unweighted(node: &char) -> Vec<char> {
+        weighted(node).into_iter().map(|(next, _)| next).collect()
+    }
+
+    /// A 4x4 grid graph with walls at `#`.
+    const MAZE: [&str; 4] = ["..#.", ".##.", "....", "#..."];
+
+    fn maze_successors(&(row, col): &(usize, usize)) -> Vec<(usize, usize)> {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .filter_map(|(dr, dc)| {
+                let row = row.checked_add_signed(dr)?;
+                let col = col.checked_add_signed(dc)?;
+                (MAZE.get(row)?.as_bytes().get(col)? == &b'.').then_some((row, col))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bfs_finds_step_counts() {
+        let search = bfs('a', unweighted);
+        assert_eq!(search.distance(&'a'), Some(0));
+        assert_eq!(search.distance(&'c'), Some(1));
+        assert_eq!(search.distance(&'d'), Some(2));
+        assert_eq!(search.distance(&'e'), None);
+        assert_eq!(search.path_to(&'d').unwrap().len(), 3);
+        assert_eq!(search.path_to(&'e'), None);
+    }
+
+    #[test]
+    fn bfs_walks_mazes() {
+        let search = bfs((0, 0), maze_successors);
+        assert_eq!(search.distance(&(0, 3)), Some(7));
+        assert_eq!(search.distance(&(3, 0)), None);
+        assert_eq!(
+            search.path_to(&(0, 3)),
+            Some(vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (2, 3),
+                (1, 3),
+                (0, 3)
+            ])
+        );
+        assert_eq!(
+            bfs_path((0, 0), maze_successors, |&(row, _)| row == 3),
+            Some(vec![(0, 0), (1, 0), (2, 0), (2, 1), (3, 1)])
+        );
+        assert_eq!(bfs_path((0, 0), maze_successors, |&p| p == (3, 0)), None);
+    }
+
+    #[test]
+    fn bfs_supports_multiple_sources() {
+        let search = bfs_multi([(0, 0), (3, 3)], maze_successors);
+        assert_eq!(search.distance(&(2, 3)), Some(1));
+        assert_eq!(search.distance(&(2, 0)), Some(2));
+        assert_eq!(search.path_to(&(0, 3)).unwrap().first(), Some(&(3, 3)));
+    }
+
+    #[test]
+    fn dijkstra_finds_cheapest_costs() {
+        let search = dijkstra('a', weighted);
+        assert_eq!(search.distance(&'b'), Some(1));
+        assert_eq!(search.distance(&'c'), Some(3));
+        assert_eq!(search.distance(&'d'), Some(2));
+        assert_eq!(search.path_to(&'c'), Some(vec!['a', 'b', 'c']));
+
+        let search = dijkstra_multi(['a', 'c'], weighted);
+        assert_eq!(search.distance(&'d'), Some(1));
+        assert_eq!(search.distance(&'b'), Some(1));
+    }
+
+    #[test]
+    fn dijkstra_path_stops_at_goal() {
+        assert_eq!(
+            dijkstra_path('a', weighted, |&node| node == 'c'),
+            Some((vec!['a', 'b', 'c'], 3))
+        );
+        assert_eq!(dijkstra_path('a', weighted, |&node| node == 'e'), None);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let goal = (0, 3);
+        let heuristic = |&(row, col): &(usize, usize)| row.abs_diff(goal.0) + col.abs_diff(goal.1);
+        let successors = |node: &(usize, usize)| {
+            maze_successors(node)
+                .into_iter()
+                .map(|next| (next, 1_usize))
+        };
+
+        let (path, cost) = astar((0, 0), successors, heuristic, |&node| node == goal).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(
+            dijkstra_path((0, 0), successors, |&node| node == goal).map(|(_, cost)| cost),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn finds_all_shortest_paths() {
+        // a -> b -> d and a -> c -> d both cost 2, a -> d costs 3.
+        let graph = |node: &char| match node {
+            'a' => vec![('b', 1), ('c', 1), ('d', 3)],
+            'b' | 'c' => vec![('d', 1_u32)],
+            _ => vec![],
+        };
+        let all = dijkstra_all(['a'], graph);
+        assert_eq!(all.distance(&'d'), Some(2));
+
+        let mut paths = all.paths_to(&'d');
+        paths.sort();
+        assert_eq!(paths, vec![vec!['a', 'b', 'd'], vec!['a', 'c', 'd']]);
+        assert_eq!(
+            all.nodes_on_paths(&'d'),
+            HashSet::from(['a', 'b', 'c', 'd'])
+        );
+        assert_eq!(all.nodes_on_paths(&'b'), HashSet::from(['a', 'b']));
+        assert!(all.paths_to(&'e').is_empty());
+    }
+
+    #[test]
+    fn handles_zero_cost_cycles() {
+        // a and b reach each other for free, and c is reached both directly and through the zero-cost d.
+        let graph = |node: &char| match node {
+            'a' => vec![('b', 0), ('c', 1), ('d', 1)],
+            'b' => vec![('a', 0), ('b', 0)],
+            'd' => vec![('c', 0_u32)],
+            _ => vec![],
+        };
+        let all = dijkstra_all(['a'], graph);
+        assert_eq!(all.paths_to(&'a'), vec![vec!['a']]);
+        assert_eq!(all.paths_to(&'b'), vec![vec!['a', 'b']]);
+        assert_eq!(all.nodes_on_paths(&'a'), HashSet::from(['a']));
+        assert_eq!(all.nodes_on_paths(&'b'), HashSet::from(['a', 'b']));
+
+        let mut paths = all.paths_to(&'c');
+        paths.sort();
+        assert_eq!(paths, vec![vec!['a', 'c'], vec!['a', 'd', 'c']]);
+        assert_eq!(all.nodes_on_paths(&'c'), HashSet::from(['a', 'c', 'd']));
+    }
+}
@@ -0,0 +1,98 @@
+/// Conversion of the values returned by solution parts into printable answers.
+use std::fmt::Display;
+
+/// Prefix of the output printed for a part that returned an error.
+pub const FAILED_PREFIX: &str = "✖ error: ";
+
+/// The outcome of a solution part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// The part returned an answer.
+    Solved(String),
+    /// The part returned `None`, i.e. it has not been implemented yet.
+    Unsolved,
+    /// The part returned an error.
+    Failed(String),
+}
+
+impl Answer {
+    /// Returns the answer, if the part was solved.
+    #[must_use]
+    pub fn solved(&self) -> Option<&str> {
+        match self {
+            Answer::Solved(answer) => Some(answer),
+            _ => None,
+        }
+    }
+}
+
+/// Values that can be returned from a solution part.
+///
+/// Implemented for `Option<T>`, `Result<T, E>` and plain answers (integers, strings, `char` and `bool`).
+pub trait IntoAnswer {
+    fn to_answer(&self) -> Answer;
+}
+
+impl<T: Display> IntoAnswer for Option<T> {
+    fn to_answer(&self) -> Answer {
+        match self {
+            Some(answer) => Answer::Solved(answer.to_string()),
+            None => Answer::Unsolved,
+        }
+    }
+}
+
+impl<T: Display, E: Display> IntoAnswer for Result<T, E> {
+    fn to_answer(&self) -> Answer {
+        match self {
+            Ok(answer) => Answer::Solved(answer.to_string()),
+            Err(e) => Answer::Failed(e.to_string()),
+        }
+    }
+}
+
+macro_rules! impl_into_answer {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAnswer for $t {
+                fn to_answer(&self) -> Answer {
+                    Answer::Solved(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_into_answer!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, String, &str, char, bool
+);
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Answer, IntoAnswer};
+
+    #[test]
+    fn converts_options() {
+        assert_eq!(Some(42).to_answer(), Answer::Solved("42".into()));
+        assert_eq!(None::<u32>.to_answer(), Answer::Unsolved);
+    }
+
+    #[test]
+    fn converts_results() {
+        assert_eq!(Ok::<_, String>(42).to_answer(), Answer::Solved("42".into()));
+        assert_eq!(
+            Err::<u32, _>("invalid digit on line 3").to_answer(),
+            Answer::Failed("invalid digit on line 3".into())
+        );
+    }
+
+    #[test]
+    fn converts_plain_values() {
+        assert_eq!(42_u64.to_answer(), Answer::Solved("42".into()));
+        assert_eq!(
+            String::from("#.\n.#").to_answer(),
+            Answer::Solved("#.\n.#".into())
+        );
+        assert_eq!(Answer::Unsolved.solved(), None);
+    }
+}
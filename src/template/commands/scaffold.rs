@@ -1,75 +1,300 @@
 use std::{
-    fs::{File, OpenOptions},
-    io::Write,
+    fs, io,
+    path::{Path, PathBuf},
     process,
 };
 
-use crate::template::Day;
+use crate::template::{aoc_cli, Day};
 
 const MODULE_TEMPLATE: &str =
     include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
-    } else {
-        file.create_new(true);
+const TEMPLATES_DIR: &str = "templates";
+const DEFAULT_TEMPLATE: &str = "day";
+const DEFAULT_RETURN_TYPE: &str = "u32";
+
+/// Values that are substituted into a module template.
+pub struct TemplateVars {
+    pub day: Day,
+    pub year: Option<u16>,
+    pub title: Option<String>,
+    pub return_type: String,
+    /// Number of part-specific example files (`<day>-<n>.txt`), if any.
+    pub examples: Option<u8>,
+}
+
+impl TemplateVars {
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("%DAY_NUMBER%", &self.day.into_inner().to_string())
+            .replace("%DAY_PADDED%", &self.day.to_string())
+            .replace(
+                "%YEAR%",
+                &self.year.map(|year| year.to_string()).unwrap_or_default(),
+            )
+            .replace("%PUZZLE_TITLE%", self.title.as_deref().unwrap_or_default())
+            .replace("%RETURN_TYPE%", &self.return_type)
+            .replace("%PART_ONE_EXAMPLES%", &self.example_tests("part_one"))
+            .replace("%PART_TWO_EXAMPLES%", &self.example_tests("part_two"))
+    }
+
+    /// Renders the entries of an `example_tests!` table for a part, one line per example file.
+    fn example_tests(&self, part_name: &str) -> String {
+        match self.examples {
+            None => format!("        test_{part_name} => None,"),
+            Some(examples) => (1..=examples)
+                .map(|example| {
+                    format!("        test_{part_name}_example_{example}: {example} => None,")
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
     }
-    file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+/// Parses the puzzle title from a puzzle description, e.g. `--- Day 8: Resonant Collinearity ---`.
+/// aoc-cli escapes the leading dashes of the heading, markdown headings are supported as well.
+fn parse_puzzle_title(markdown: &str) -> Option<String> {
+    markdown.lines().find_map(|line| {
+        let title = line
+            .trim_start_matches(['#', ' ', '\\'])
+            .strip_prefix("--- Day ")?
+            .strip_suffix("---")?
+            .split_once(':')?
+            .1
+            .trim();
+        Some(title.to_string())
+    })
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Returns the candidate paths for a named template, year-specific overrides first.
+fn template_paths(name: &str, year: Option<u16>) -> Vec<PathBuf> {
+    let file_name = format!("{name}.rs.tmpl");
+    let root = PathBuf::from(TEMPLATES_DIR);
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
+    year.map(|year| root.join(year.to_string()).join(&file_name))
+        .into_iter()
+        .chain([root.join(&file_name)])
+        .collect()
+}
+
+/// Resolves the module template to use for scaffolding.
+///
+/// Looks for `templates/<year>/<name>.rs.tmpl` and `templates/<name>.rs.tmpl`, in that order.
+/// If no template name is given and no `day` template exists, the built-in template is used.
+fn resolve_template(name: Option<&str>, year: Option<u16>) -> Result<String, String> {
+    let paths = template_paths(name.unwrap_or(DEFAULT_TEMPLATE), year);
+
+    if let Some(path) = paths.iter().find(|path| path.is_file()) {
+        return fs::read_to_string(path)
+            .map_err(|e| format!("could not read template \"{}\": {e}", path.display()));
+    }
+
+    match name {
+        None => Ok(MODULE_TEMPLATE.to_string()),
+        Some(name) => Err(format!(
+            "template \"{name}\" not found, looked in: {}",
+            paths
+                .iter()
+                .map(|path| format!("\"{}\"", path.display()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// A file generated by `scaffold`.
+pub struct ScaffoldFile {
+    pub label: &'static str,
+    pub path: PathBuf,
+    pub contents: String,
+    /// Whether an existing, non-empty file is replaced (after backing it up) instead of skipped.
+    pub overwrite: bool,
+}
+
+/// What happened to a single file during scaffolding.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The file was written. `existed` is set if an empty file was present before.
+    Created { existed: bool },
+    /// A non-empty file was present and left untouched.
+    Skipped,
+    /// A non-empty file was moved to the contained path before writing the new file.
+    BackedUp(PathBuf),
+}
+
+/// Writes all scaffold files or none of them.
+///
+/// Existing non-empty files are never truncated: they are either skipped or, for files that allow overwriting,
+/// backed up first. If any step fails, every previously applied step is rolled back.
+pub fn write_files(files: &[ScaffoldFile]) -> Result<Vec<Outcome>, io::Error> {
+    let mut outcomes: Vec<Outcome> = Vec::with_capacity(files.len());
+
+    for file in files {
+        match write_file(file) {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(e) => {
+                rollback(&files[..outcomes.len()], &outcomes);
+                return Err(io::Error::new(
+                    e.kind(),
+                    format!("{} \"{}\": {e}", file.label, file.path.display()),
+                ));
+            }
         }
+    }
+
+    Ok(outcomes)
+}
+
+fn write_file(file: &ScaffoldFile) -> Result<Outcome, io::Error> {
+    let existing_len = match fs::metadata(&file.path) {
+        Ok(metadata) => Some(metadata.len()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
-        }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+    let outcome = match existing_len {
+        None => Outcome::Created { existed: false },
+        Some(0) => Outcome::Created { existed: true },
+        Some(_) if !file.overwrite => return Ok(Outcome::Skipped),
+        Some(_) => {
+            let backup_path = backup_path(&file.path);
+            fs::rename(&file.path, &backup_path)?;
+            Outcome::BackedUp(backup_path)
         }
+    };
+
+    if let Err(e) = fs::write(&file.path, &file.contents) {
+        rollback(std::slice::from_ref(file), std::slice::from_ref(&outcome));
+        return Err(e);
     }
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+    Ok(outcome)
+}
+
+/// Returns the first free backup path for a file, e.g. `01.rs.bak`, `01.rs.1.bak`, ...
+fn backup_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    (0..)
+        .map(|i| match i {
+            0 => path.with_file_name(format!("{file_name}.bak")),
+            i => path.with_file_name(format!("{file_name}.{i}.bak")),
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+/// Reverts applied steps in reverse order. Errors are reported, but do not stop the rollback.
+fn rollback(files: &[ScaffoldFile], outcomes: &[Outcome]) {
+    for (file, outcome) in files.iter().zip(outcomes).rev() {
+        let result = match outcome {
+            Outcome::Created { existed: false } => fs::remove_file(&file.path),
+            Outcome::Created { existed: true } => fs::write(&file.path, ""),
+            Outcome::Skipped => Ok(()),
+            Outcome::BackedUp(backup_path) => fs::rename(backup_path, &file.path),
+        };
+
+        match result {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!("Failed to roll back \"{}\": {e}", file.path.display()),
         }
+    }
+}
+
+fn print_outcome(file: &ScaffoldFile, outcome: &Outcome) {
+    let path = file.path.display();
+    match outcome {
+        Outcome::Created { .. } => println!("Created {} \"{path}\"", file.label),
+        Outcome::Skipped => println!("Skipped {} \"{path}\" (already exists)", file.label),
+        Outcome::BackedUp(backup_path) => println!(
+            "Created {} \"{path}\" (backed up previous file to \"{}\")",
+            file.label,
+            backup_path.display()
+        ),
+    }
+}
+
+pub fn handle(
+    day: Day,
+    overwrite: bool,
+    template: Option<&str>,
+    return_type: Option<&str>,
+    examples: Option<u8>,
+) {
+    if examples == Some(0) {
+        eprintln!("`--examples` expects a number of example files greater than 0.");
+        process::exit(1);
+    }
+
+    let input_path = format!("data/inputs/{day}.txt");
+    let example_paths: Vec<String> = match examples {
+        None => vec![format!("data/examples/{day}.txt")],
+        Some(examples) => (1..=examples)
+            .map(|example| format!("data/examples/{day}-{example}.txt"))
+            .collect(),
+    };
+    let module_path = format!("src/bin/{day}.rs");
+
+    let year = aoc_cli::get_year();
+
+    let module_template = match resolve_template(template, year) {
+        Ok(module_template) => module_template,
         Err(e) => {
-            eprintln!("Failed to create input file: {e}");
+            eprintln!("Failed to resolve module template: {e}");
             process::exit(1);
         }
-    }
+    };
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+    let vars = TemplateVars {
+        day,
+        year,
+        title: fs::read_to_string(aoc_cli::get_puzzle_path(day))
+            .ok()
+            .and_then(|markdown| parse_puzzle_title(&markdown)),
+        return_type: return_type.unwrap_or(DEFAULT_RETURN_TYPE).to_string(),
+        examples,
+    };
+
+    let mut files = vec![
+        ScaffoldFile {
+            label: "module file",
+            path: module_path.into(),
+            contents: vars.render(&module_template),
+            overwrite,
+        },
+        ScaffoldFile {
+            label: "input file",
+            path: input_path.into(),
+            contents: String::new(),
+            overwrite: false,
+        },
+    ];
+
+    files.extend(example_paths.into_iter().map(|path| ScaffoldFile {
+        label: "example file",
+        path: path.into(),
+        contents: String::new(),
+        overwrite: false,
+    }));
+
+    match write_files(&files) {
+        Ok(outcomes) => {
+            files
+                .iter()
+                .zip(&outcomes)
+                .for_each(|(file, outcome)| print_outcome(file, outcome));
+
+            if !overwrite && outcomes[0] == Outcome::Skipped {
+                println!("Use `--overwrite` to replace the existing module file.");
+            }
         }
         Err(e) => {
-            eprintln!("Failed to create example file: {e}");
+            eprintln!("Failed to scaffold, no files were changed: {e}");
             process::exit(1);
         }
     }
@@ -77,3 +302,194 @@ pub fn handle(day: Day, overwrite: bool) {
     println!("---");
     println!("🎄 Type `cargo solve {day}` to run your solution.");
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::{
+        env, fs,
+        path::{Path, PathBuf},
+    };
+
+    use super::{
+        parse_puzzle_title, template_paths, write_files, Outcome, ScaffoldFile, TemplateVars,
+    };
+    use crate::day;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-scaffold-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn scaffold_file(dir: &Path, name: &str, contents: &str, overwrite: bool) -> ScaffoldFile {
+        ScaffoldFile {
+            label: "file",
+            path: dir.join(name),
+            contents: contents.into(),
+            overwrite,
+        }
+    }
+
+    #[test]
+    fn creates_missing_files() {
+        let dir = temp_dir("create");
+        fs::write(dir.join("empty.txt"), "").unwrap();
+
+        let outcomes = write_files(&[
+            scaffold_file(&dir, "module.rs", "module", false),
+            scaffold_file(&dir, "empty.txt", "", false),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Created { existed: false },
+                Outcome::Created { existed: true }
+            ]
+        );
+        assert_eq!(fs::read_to_string(dir.join("module.rs")).unwrap(), "module");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn skips_non_empty_files() {
+        let dir = temp_dir("skip");
+        fs::write(dir.join("input.txt"), "real input").unwrap();
+
+        let outcomes = write_files(&[scaffold_file(&dir, "input.txt", "", false)]).unwrap();
+
+        assert_eq!(outcomes, vec![Outcome::Skipped]);
+        assert_eq!(
+            fs::read_to_string(dir.join("input.txt")).unwrap(),
+            "real input"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn backs_up_overwritten_files() {
+        let dir = temp_dir("backup");
+        fs::write(dir.join("module.rs"), "old").unwrap();
+        fs::write(dir.join("module.rs.bak"), "older").unwrap();
+
+        let outcomes = write_files(&[scaffold_file(&dir, "module.rs", "new", true)]).unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![Outcome::BackedUp(dir.join("module.rs.1.bak"))]
+        );
+        assert_eq!(fs::read_to_string(dir.join("module.rs")).unwrap(), "new");
+        assert_eq!(
+            fs::read_to_string(dir.join("module.rs.bak")).unwrap(),
+            "older"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("module.rs.1.bak")).unwrap(),
+            "old"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rolls_back_on_failure() {
+        let dir = temp_dir("rollback");
+        fs::write(dir.join("module.rs"), "old").unwrap();
+        fs::write(dir.join("empty.txt"), "").unwrap();
+
+        let result = write_files(&[
+            scaffold_file(&dir, "module.rs", "new", true),
+            scaffold_file(&dir, "empty.txt", "contents", false),
+            scaffold_file(&dir, "created.txt", "", false),
+            scaffold_file(&dir, "missing/input.txt", "", false),
+        ]);
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(dir.join("module.rs")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(dir.join("empty.txt")).unwrap(), "");
+        assert!(!dir.join("module.rs.bak").exists());
+        assert!(!dir.join("created.txt").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn renders_variables() {
+        let vars = TemplateVars {
+            day: day!(9),
+            year: Some(2024),
+            title: Some("Disk Fragmenter".into()),
+            return_type: "u64".into(),
+            examples: None,
+        };
+        assert_eq!(
+            vars.render("%YEAR% %DAY_NUMBER% %DAY_PADDED% %PUZZLE_TITLE% -> %RETURN_TYPE%"),
+            "2024 9 09 Disk Fragmenter -> u64"
+        );
+    }
+
+    #[test]
+    fn renders_missing_variables_empty() {
+        let vars = TemplateVars {
+            day: day!(9),
+            year: None,
+            title: None,
+            return_type: "u32".into(),
+            examples: None,
+        };
+        assert_eq!(vars.render("[%YEAR%][%PUZZLE_TITLE%]"), "[][]");
+        assert_eq!(
+            vars.render("%PART_TWO_EXAMPLES%"),
+            "        test_part_two => None,"
+        );
+    }
+
+    #[test]
+    fn renders_example_tests() {
+        let vars = TemplateVars {
+            day: day!(9),
+            year: None,
+            title: None,
+            return_type: "u32".into(),
+            examples: Some(2),
+        };
+        assert_eq!(
+            vars.render("%PART_ONE_EXAMPLES%"),
+            [
+                "        test_part_one_example_1: 1 => None,",
+                "        test_part_one_example_2: 2 => None,"
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn parses_puzzle_title() {
+        let markdown = "\\--- Day 8: Resonant Collinearity ---\n----------\n\nYou find yourself...";
+        assert_eq!(
+            parse_puzzle_title(markdown).as_deref(),
+            Some("Resonant Collinearity")
+        );
+        let markdown = "## --- Day 8: Resonant Collinearity ---\n\nYou find yourself...";
+        assert_eq!(
+            parse_puzzle_title(markdown).as_deref(),
+            Some("Resonant Collinearity")
+        );
+        assert_eq!(parse_puzzle_title("no title here"), None);
+    }
+
+    #[test]
+    fn prefers_year_templates() {
+        assert_eq!(
+            template_paths("grid", Some(2024)),
+            vec![
+                PathBuf::from("templates/2024/grid.rs.tmpl"),
+                PathBuf::from("templates/grid.rs.tmpl")
+            ]
+        );
+        assert_eq!(
+            template_paths("day", None),
+            vec![PathBuf::from("templates/day.rs.tmpl")]
+        );
+    }
+}
@@ -2,7 +2,23 @@ use std::process::{Command, Stdio};
 
 use crate::template::Day;
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// The `--visualize` flag and its options, forwarded to the solution binary.
+#[derive(Debug, Default)]
+pub struct VisualizeArgs {
+    pub frame_delay: Option<u64>,
+    pub export: Option<String>,
+    pub cell_size: Option<usize>,
+}
+
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    inputs_dir: Option<String>,
+    debug: bool,
+    visualize: Option<VisualizeArgs>,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -16,6 +32,11 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push("--release".to_string());
     }
 
+    let export = visualize.as_ref().and_then(|args| args.export.as_deref());
+    if matches!(export, Some("png" | "gif")) {
+        cmd_args.extend(["--features".to_string(), "image-export".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -23,6 +44,37 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    if let Some(inputs_dir) = inputs_dir {
+        cmd_args.push("--inputs-dir".to_string());
+        cmd_args.push(inputs_dir);
+    }
+
+    if debug {
+        if release || dhat {
+            eprintln!("Warning: `--debug` prints nothing in optimized builds, `debug!` is compiled out of them.");
+        }
+        cmd_args.push("--debug".to_string());
+    }
+
+    if let Some(visualize) = visualize {
+        cmd_args.push("--visualize".to_string());
+
+        if let Some(frame_delay) = visualize.frame_delay {
+            cmd_args.push("--frame-delay".to_string());
+            cmd_args.push(frame_delay.to_string());
+        }
+
+        if let Some(export) = visualize.export {
+            cmd_args.push("--export".to_string());
+            cmd_args.push(export);
+        }
+
+        if let Some(cell_size) = visualize.cell_size {
+            cmd_args.push("--cell-size".to_string());
+            cmd_args.push(cell_size.to_string());
+        }
+    }
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -0,0 +1,298 @@
+/// Support for running a solution against a directory of puzzle inputs (e.g. inputs of several accounts).
+///
+/// Every `*.txt` file in the directory is treated as one input. Expected answers can be placed next to an input
+/// as sidecar files named `<input>-<part>.answer`, e.g. `alice.txt` is checked against `alice-1.answer` and `alice-2.answer`.
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::template::answer::{Answer, FAILED_PREFIX};
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+/// A single puzzle input read from an inputs directory.
+#[derive(Debug)]
+pub struct InputFile {
+    pub name: String,
+    pub contents: String,
+    pub answers: [Option<String>; 2],
+}
+
+impl InputFile {
+    /// Returns the expected answer for a part, if a sidecar answer file exists.
+    #[must_use]
+    pub fn expected(&self, part: u8) -> Option<&str> {
+        self.answers
+            .get(usize::from(part).checked_sub(1)?)?
+            .as_deref()
+    }
+}
+
+/// Returns the value of the `--inputs-dir` argument passed to a solution binary.
+#[must_use]
+pub fn dir_from_args() -> Option<PathBuf> {
+    let args: Vec<String> = env::args().collect();
+    let index = args.iter().position(|x| x == "--inputs-dir")?;
+    args.get(index + 1).map(PathBuf::from)
+}
+
+/// Reads every `*.txt` file of a directory, sorted by file name, along with its sidecar answers.
+pub fn read_dir(dir: &Path) -> Result<Vec<InputFile>, io::Error> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            Ok(InputFile {
+                name: path
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                contents: fs::read_to_string(&path)?,
+                answers: [1, 2].map(|part| read_answer(dir, &stem, part)),
+            })
+        })
+        .collect()
+}
+
+fn read_answer(dir: &Path, stem: &str, part: u8) -> Option<String> {
+    fs::read_to_string(dir.join(format!("{stem}-{part}.answer")))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// The outcome of running one part against one input.
+#[derive(Debug)]
+pub struct PartOutcome {
+    pub part: u8,
+    pub answer: Answer,
+    pub expected: Option<String>,
+    pub duration: Duration,
+    pub samples: u128,
+}
+
+impl PartOutcome {
+    /// Returns `false` if the part failed, or an expected answer is known and the result does not match it.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        match (&self.answer, &self.expected) {
+            (Answer::Failed(_), _) => false,
+            (answer, Some(expected)) => answer.solved() == Some(expected.as_str()),
+            (_, None) => true,
+        }
+    }
+
+    fn format_cell(&self) -> String {
+        let answer = match &self.answer {
+            Answer::Solved(answer) => answer.clone(),
+            Answer::Unsolved => "✖".to_string(),
+            Answer::Failed(message) => format!("{FAILED_PREFIX}{message}"),
+        };
+        let status = match &self.expected {
+            Some(_) if self.is_ok() => " ✔".to_string(),
+            Some(_) if matches!(self.answer, Answer::Failed(_)) => String::new(),
+            Some(expected) => format!(" ✖ expected {expected}"),
+            None => String::new(),
+        };
+        let timing = if self.samples == 1 {
+            format!("{:.1?}", self.duration)
+        } else {
+            format!("{:.1?} @ {} samples", self.duration, self.samples)
+        };
+        format!("{answer}{status} ({timing})")
+    }
+}
+
+/// Collects part outcomes for every input and prints them as a table.
+#[derive(Debug)]
+pub struct InputsReport {
+    names: Vec<String>,
+    outcomes: Vec<Vec<PartOutcome>>,
+}
+
+impl InputsReport {
+    #[must_use]
+    pub fn new(inputs: &[InputFile]) -> Self {
+        Self {
+            names: inputs.iter().map(|input| input.name.clone()).collect(),
+            outcomes: inputs.iter().map(|_| vec![]).collect(),
+        }
+    }
+
+    pub fn push(&mut self, input_index: usize, outcome: PartOutcome) {
+        self.outcomes[input_index].push(outcome);
+    }
+
+    /// Returns `true` if every part matched its expected answer (where one is present).
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.outcomes.iter().flatten().all(PartOutcome::is_ok)
+    }
+
+    #[must_use]
+    pub fn format_table(&self) -> String {
+        let parts: Vec<u8> = self
+            .outcomes
+            .first()
+            .map(|row| row.iter().map(|outcome| outcome.part).collect())
+            .unwrap_or_default();
+
+        let header: Vec<String> = std::iter::once("Input".to_string())
+            .chain(parts.iter().map(|part| format!("Part {part}")))
+            .collect();
+
+        let rows: Vec<Vec<String>> = self
+            .names
+            .iter()
+            .zip(&self.outcomes)
+            .map(|(name, outcomes)| {
+                std::iter::once(name.clone())
+                    .chain(outcomes.iter().map(PartOutcome::format_cell))
+                    .collect()
+            })
+            .collect();
+
+        let widths: Vec<usize> = (0..header.len())
+            .map(|col| {
+                std::iter::once(&header)
+                    .chain(&rows)
+                    .filter_map(|row| row.get(col))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let format_row = |row: &[String]| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    let padding = width - cell.chars().count();
+                    format!("{cell}{}", " ".repeat(padding))
+                })
+                .collect::<Vec<String>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = vec![
+            format_row(&header),
+            widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<String>>()
+                .join("-|-"),
+        ];
+        lines.extend(rows.iter().map(|row| format_row(row)));
+        lines.join("\n")
+    }
+
+    pub fn print(&self) {
+        let mut lines = self
+            .format_table()
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if let Some(header) = lines.first_mut() {
+            *header = format!("{ANSI_BOLD}{header}{ANSI_RESET}");
+        }
+        println!("{}", lines.join("\n"));
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{InputFile, InputsReport, PartOutcome};
+    use crate::template::answer::Answer;
+
+    fn outcome(part: u8, answer: Option<&str>, expected: Option<&str>) -> PartOutcome {
+        PartOutcome {
+            part,
+            answer: answer.map_or(Answer::Unsolved, |answer| Answer::Solved(answer.into())),
+            expected: expected.map(str::to_string),
+            duration: Duration::from_micros(12),
+            samples: 1,
+        }
+    }
+
+    fn inputs() -> Vec<InputFile> {
+        ["alice.txt", "bob.txt"]
+            .map(|name| InputFile {
+                name: name.into(),
+                contents: String::new(),
+                answers: [None, None],
+            })
+            .into()
+    }
+
+    #[test]
+    fn formats_table() {
+        let mut report = InputsReport::new(&inputs());
+        report.push(0, outcome(1, Some("42"), Some("42")));
+        report.push(0, outcome(2, None, None));
+        report.push(1, outcome(1, Some("7"), Some("8")));
+        report.push(1, outcome(2, Some("100"), None));
+
+        assert_eq!(
+            report.format_table(),
+            [
+                "Input     | Part 1                  | Part 2",
+                "----------|-------------------------|-------------",
+                "alice.txt | 42 ✔ (12.0µs)           | ✖ (12.0µs)",
+                "bob.txt   | 7 ✖ expected 8 (12.0µs) | 100 (12.0µs)",
+            ]
+            .join("\n")
+        );
+        assert!(!report.is_ok());
+    }
+
+    #[test]
+    fn passes_without_expected_answers() {
+        let mut report = InputsReport::new(&inputs());
+        report.push(0, outcome(1, Some("1"), None));
+        report.push(1, outcome(1, None, None));
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn fails_on_errors() {
+        let mut report = InputsReport::new(&inputs());
+        report.push(
+            0,
+            PartOutcome {
+                answer: Answer::Failed("bad input".into()),
+                ..outcome(1, None, None)
+            },
+        );
+        assert!(!report.is_ok());
+        assert!(report
+            .format_table()
+            .contains("✖ error: bad input (12.0µs)"));
+    }
+
+    #[test]
+    fn reads_expected_answers() {
+        let input = InputFile {
+            name: "alice.txt".into(),
+            contents: String::new(),
+            answers: [Some("1".into()), None],
+        };
+        assert_eq!(input.expected(1), Some("1"));
+        assert_eq!(input.expected(2), None);
+        assert_eq!(input.expected(3), None);
+    }
+}
@@ -1,7 +1,9 @@
 use std::{env, fs};
 
+pub mod answer;
 pub mod aoc_cli;
 pub mod commands;
+pub mod inputs;
 pub mod runner;
 
 pub use day::*;
@@ -39,6 +41,8 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Parts can return `Option<T>`, `Result<T, E>` or a plain answer, see [`answer::IntoAnswer`].
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -61,8 +65,73 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
+
+            $crate::debug::set_enabled($crate::debug::from_args());
+
+            if let Some(dir) = $crate::template::inputs::dir_from_args() {
+                let inputs = match $crate::template::inputs::read_dir(&dir) {
+                    Ok(inputs) => inputs,
+                    Err(e) => {
+                        eprintln!("Failed to read inputs directory {}: {e}", dir.display());
+                        std::process::exit(1);
+                    }
+                };
+                let mut report = $crate::template::inputs::InputsReport::new(&inputs);
+                $( run_part_inputs($func, &inputs, $part, &mut report); )*
+                report.print();
+                if !report.is_ok() {
+                    std::process::exit(1);
+                }
+                return;
+            }
+
             let input = $crate::template::read_file("inputs", DAY);
+
+            match $crate::visualize::Playback::from_args() {
+                Ok(Some(playback)) => {
+                    $( run_part_visualized($func, &input, DAY, $part, &playback); )*
+                    return;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            }
+
             $( run_part($func, &input, DAY, $part); )*
         }
     };
 }
+
+/// Generates one test per example for a solution part, comparing the result against an expected answer.
+///
+/// Each entry is `test_name => expected` to read the day's example file (e.g. `01.txt`),
+/// or `test_name: n => expected` to read the part-specific example file `01-n.txt`.
+///
+/// ```ignore
+/// advent_of_code::example_tests! {
+///     part_one,
+///     test_part_one_example_1: 1 => Some(11),
+///     test_part_one_example_2: 2 => Some(42),
+/// }
+/// ```
+#[macro_export]
+macro_rules! example_tests {
+    ($func:ident, $( $name:ident $(: $example:expr)? => $expected:expr ),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                let result = $func(&$crate::example_tests!(@read $($example)?));
+                assert_eq!(result, $expected);
+            }
+        )*
+    };
+
+    (@read) => {
+        $crate::template::read_file("examples", DAY)
+    };
+    (@read $example:expr) => {
+        $crate::template::read_file_part("examples", DAY, $example)
+    };
+}
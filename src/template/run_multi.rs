@@ -9,6 +9,7 @@ use super::{
 
 pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut failures: Vec<(Day, Vec<u8>)> = vec![];
 
     let mut need_space = false;
 
@@ -31,9 +32,26 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             } else {
                 let val = child_commands::parse_exec_time(&output, day);
                 timings.push(val);
+
+                let failed_parts = child_commands::parse_failed_parts(&output);
+                if !failed_parts.is_empty() {
+                    failures.push((day, failed_parts));
+                }
             }
         });
 
+    if !failures.is_empty() {
+        println!("\n{ANSI_BOLD}Failed:{ANSI_RESET}");
+        for (day, parts) in &failures {
+            let parts = parts
+                .iter()
+                .map(|part| format!("Part {part}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("Day {day}: {parts}");
+        }
+    }
+
     if is_timed {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
@@ -68,7 +86,7 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
     use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use crate::template::{answer::FAILED_PREFIX, Day};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
@@ -164,6 +182,21 @@ pub mod child_commands {
         timings
     }
 
+    /// Returns the parts that printed an error instead of an answer.
+    pub fn parse_failed_parts(output: &[String]) -> Vec<u8> {
+        output
+            .iter()
+            .filter_map(|l| {
+                // intermediate results are overwritten with a carriage return, only look at the final result.
+                let (part, rest) = l.rsplit('\r').next()?.split_once(": ")?;
+                if !rest.starts_with(FAILED_PREFIX) {
+                    return None;
+                }
+                part.strip_prefix("Part ")?.parse().ok()
+            })
+            .collect()
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -174,7 +207,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -205,7 +238,7 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_failed_parts};
 
         use crate::day;
 
@@ -253,5 +286,24 @@ pub mod child_commands {
             assert_eq!(res.part_1.is_none(), true);
             assert_eq!(res.part_2.is_none(), true);
         }
+
+        #[test]
+        fn parses_failed_parts() {
+            let output = [
+                "Part 1: 42 (1.0ms @ 10 samples)".into(),
+                "Part 2: ✖\rPart 2: ✖ error: invalid digit on line 3".into(),
+                "".into(),
+            ];
+            let res = parse_exec_time(&output, day!(1));
+            assert_eq!(res.part_1.unwrap(), "1.0ms");
+            assert_eq!(res.part_2.is_none(), true);
+            assert_eq!(parse_failed_parts(&output), vec![2]);
+        }
+
+        #[test]
+        fn does_not_parse_unsolved_parts_as_failed() {
+            let output = ["Part 1: ✖        ".into(), "Part 2: 0 (1.0ms)".into()];
+            assert!(parse_failed_parts(&output).is_empty());
+        }
     }
 }
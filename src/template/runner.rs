@@ -1,24 +1,94 @@
 /// Encapsulates code that interacts with solution functions.
-use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
 use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::template::answer::{Answer, IntoAnswer, FAILED_PREFIX};
+use crate::template::inputs::{InputFile, InputsReport, PartOutcome};
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::visualize::{self, Playback};
+use crate::{debug, memo};
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+pub fn run_part<I: Copy, R: IntoAnswer>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
+    debug::set_context(day, part);
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples) = run_timed(func, input, |result| {
+        print_result(&result.to_answer(), &part_str, "");
+    });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let answer = result.to_answer();
+    print_result(&answer, &part_str, &format_duration(&duration, samples));
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    if let Some(answer) = answer.solved() {
+        submit_result(answer, day, part);
+    }
+}
+
+/// Run a solution part once while recording frames, then play or export them before printing the result.
+pub fn run_part_visualized<I: Copy, R: IntoAnswer>(
+    func: impl Fn(I) -> R,
+    input: I,
+    day: Day,
+    part: u8,
+    playback: &Playback,
+) {
+    debug::set_context(day, part);
+    visualize::set_enabled(true);
+    let timer = Instant::now();
+    let result = func(input);
+    let duration = timer.elapsed();
+    visualize::set_enabled(false);
+
+    let frames = visualize::take_frames();
+    match playback.export {
+        Some(export) if !frames.is_empty() => match export.write(&frames, day, part) {
+            Ok(paths) => println!("Wrote {} file(s) to data/visuals/{day}/.", paths.len()),
+            Err(e) => eprintln!("{FAILED_PREFIX}could not write images: {e}"),
+        },
+        Some(_) => {}
+        None => playback.play(&frames),
+    }
+
+    println!("\r{}", frames_summary(frames.len()));
+    print_result(
+        &result.to_answer(),
+        &format!("Part {part}"),
+        &format_duration(&duration, 1),
+    );
+}
+
+fn frames_summary(count: usize) -> String {
+    match count {
+        0 => "No frames were emitted.".to_string(),
+        1 => "Recorded 1 frame.".to_string(),
+        n => format!("Recorded {n} frames."),
+    }
+}
+
+/// Run a solution part against every input of an inputs directory, collecting the outcomes into `report`.
+pub fn run_part_inputs<R: IntoAnswer>(
+    func: impl Fn(&str) -> R,
+    inputs: &[InputFile],
+    part: u8,
+    report: &mut InputsReport,
+) {
+    for (index, input) in inputs.iter().enumerate() {
+        let (result, duration, samples) = run_timed(&func, input.contents.as_str(), |_| {});
+
+        report.push(
+            index,
+            PartOutcome {
+                part,
+                answer: result.to_answer(),
+                expected: input.expected(part).map(str::to_string),
+                duration,
+                samples,
+            },
+        );
     }
 }
 
@@ -42,7 +112,12 @@ fn run_timed<I: Copy, T>(
     hook(&result);
 
     let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+        // only the first run is logged, the output would distort the benchmark.
+        let debug = debug::is_enabled();
+        debug::set_enabled(false);
+        let run = bench(func, input, &base_time);
+        debug::set_enabled(debug);
+        run
     } else {
         (base_time, 1)
     };
@@ -62,6 +137,8 @@ fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (
     let mut timers: Vec<Duration> = vec![];
 
     for _ in 0..bench_iterations {
+        // memoized results of the previous sample would turn this one into cache lookups.
+        memo::clear_all();
         let timer = Instant::now();
         black_box(func(black_box(input)));
         timers.push(timer.elapsed());
@@ -90,12 +167,12 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result(answer: &Answer, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
-    match result {
-        Some(result) => {
-            if result.to_string().contains('\n') {
+    match answer {
+        Answer::Solved(result) => {
+            if result.contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
                     print!("{str}");
@@ -114,7 +191,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Answer::Unsolved => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -122,14 +199,23 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        Answer::Failed(message) => {
+            // NOTE: the duration is omitted so failed parts are not picked up as timings.
+            if is_intermediate_result {
+                print!("{part}: ✖");
+            } else {
+                print!("\r");
+                println!("{part}: {FAILED_PREFIX}{message}");
+            }
+        }
     }
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
+fn submit_result(
+    result: &str,
     day: Day,
     part: u8,
 ) -> Option<Result<Output, aoc_cli::AocCommandError>> {
@@ -161,5 +247,5 @@ fn submit_result<T: Display>(
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    Some(aoc_cli::submit(day, part, result))
 }
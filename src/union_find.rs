@@ -0,0 +1,138 @@
+//! Disjoint sets over the indices `0..n`, with path compression and union by rank.
+
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /// Creates `n` singleton sets.
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+            ranks: vec![0; n],
+            sizes: vec![1; n],
+            sets: n,
+        }
+    }
+
+    /// The number of elements.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// The number of disjoint sets.
+    #[must_use]
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// The representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // point every element on the way directly at the root.
+        let mut x = x;
+        while self.parents[x] != root {
+            let parent = self.parents[x];
+            self.parents[x] = root;
+            x = parent;
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning `false` if they already were the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.ranks[a] < self.ranks[b] {
+            (a, b) = (b, a);
+        }
+        if self.ranks[a] == self.ranks[b] {
+            self.ranks[a] += 1;
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.sets -= 1;
+        true
+    }
+
+    #[must_use = "use `union` to merge sets"]
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The size of the set containing `x`.
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// All sets, each in ascending order, ordered by their smallest element.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut group_of_root = vec![usize::MAX; self.len()];
+        let mut groups: Vec<Vec<usize>> = vec![];
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if group_of_root[root] == usize::MAX {
+                group_of_root[root] = groups.len();
+                groups.push(vec![]);
+            }
+            groups[group_of_root[root]].push(x);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_sets() {
+        let mut sets = UnionFind::new(8);
+        assert_eq!(sets.set_count(), 8);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.union(5, 6));
+
+        assert_eq!(sets.set_count(), 4);
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 5));
+        assert_eq!(sets.size_of(2), 4);
+        assert_eq!(sets.size_of(4), 1);
+        assert_eq!(
+            sets.groups(),
+            vec![vec![0, 1, 2, 3], vec![4], vec![5, 6], vec![7]]
+        );
+    }
+
+    #[test]
+    fn handles_long_chains() {
+        let n = 100_000;
+        let mut sets = UnionFind::new(n);
+        for i in 1..n {
+            sets.union(i - 1, i);
+        }
+        assert_eq!(sets.set_count(), 1);
+        assert_eq!(sets.size_of(n - 1), n);
+        assert!(sets.same_set(0, n - 1));
+    }
+}
@@ -0,0 +1,311 @@
+//! Exports frames as images, one filled square per cell.
+//!
+//! PPM is always available. PNG and animated GIF need the `image-export` feature.
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
+};
+
+use super::{Color, Frame};
+use crate::template::Day;
+
+pub type Rgb = [u8; 3];
+
+/// Maps the cells of a frame to colours.
+///
+/// Highlighted cells take their background colour, paths and markers their foreground colour.
+/// All other cells are coloured by their character, falling back to `foreground`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    chars: Vec<(char, Rgb)>,
+    colors: [Rgb; 9],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            background: [15, 15, 35],
+            foreground: [204, 204, 204],
+            chars: vec![
+                ('.', [15, 15, 35]),
+                (' ', [15, 15, 35]),
+                ('#', [110, 110, 130]),
+            ],
+            colors: [
+                [0, 0, 0],
+                [230, 60, 60],
+                [0, 200, 80],
+                [255, 215, 0],
+                [60, 120, 230],
+                [200, 80, 200],
+                [0, 200, 200],
+                [255, 255, 255],
+                [128, 128, 128],
+            ],
+        }
+    }
+}
+
+impl Palette {
+    /// Draws cells with the character `ch` in `rgb`.
+    #[must_use]
+    pub fn with_char(mut self, ch: char, rgb: Rgb) -> Self {
+        self.chars.retain(|&(c, _)| c != ch);
+        self.chars.push((ch, rgb));
+        self
+    }
+
+    /// Draws overlays with the terminal colour `color` in `rgb`.
+    #[must_use]
+    pub fn with_color(mut self, color: Color, rgb: Rgb) -> Self {
+        self.colors[color as usize] = rgb;
+        self
+    }
+
+    #[must_use]
+    pub fn color(&self, color: Color) -> Rgb {
+        self.colors[color as usize]
+    }
+
+    fn cell_color(&self, frame: &Frame, position: (usize, usize)) -> Rgb {
+        let Some((ch, style)) = frame.cell(position) else {
+            return self.background;
+        };
+        match (style.background, style.foreground) {
+            (Some(color), _) | (None, Some(color)) => self.color(color),
+            (None, None) => self
+                .chars
+                .iter()
+                .find(|&&(c, _)| c == ch)
+                .map_or(self.foreground, |&(_, rgb)| rgb),
+        }
+    }
+}
+
+static PALETTE: Mutex<Option<Palette>> = Mutex::new(None);
+
+/// Sets the palette used when exporting frames, e.g. to give a day's own characters distinct colours.
+pub fn set_palette(palette: Palette) {
+    *PALETTE.lock().unwrap() = Some(palette);
+}
+
+/// The palette set with [`set_palette`], or the default one.
+#[must_use]
+pub fn palette() -> Palette {
+    PALETTE.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// An RGB image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major pixels.
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Draws every cell of the frame as a `cell_size` x `cell_size` square.
+    #[must_use]
+    pub fn render(frame: &Frame, cell_size: usize, palette: &Palette) -> Self {
+        let (width, height) = (frame.width() * cell_size, frame.height() * cell_size);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(palette.cell_color(frame, (y / cell_size, x / cell_size)));
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.pixels.iter().flatten().copied().collect()
+    }
+
+    /// Writes a binary PPM (`P6`) image.
+    pub fn write_ppm(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.bytes())
+    }
+
+    #[cfg(feature = "image-export")]
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.bytes()))
+            .map_err(io::Error::other)
+    }
+}
+
+/// Writes the images as an animated GIF that loops forever.
+#[cfg(feature = "image-export")]
+pub fn write_gif(images: &[Image], delay: Duration, writer: impl Write) -> io::Result<()> {
+    let dimension =
+        |n: usize| u16::try_from(n).map_err(|_| io::Error::other("image is too large for a GIF"));
+    let width = dimension(images.iter().map(|image| image.width).max().unwrap_or(0))?;
+    let height = dimension(images.iter().map(|image| image.height).max().unwrap_or(0))?;
+
+    let mut encoder = gif::Encoder::new(writer, width, height, &[]).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    for image in images {
+        let (width, height) = (dimension(image.width)?, dimension(image.height)?);
+        let mut frame = gif::Frame::from_rgb_speed(width, height, &image.bytes(), 10);
+        frame.delay = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One `.ppm` file per frame.
+    Ppm,
+    /// One `.png` file per frame.
+    #[cfg(feature = "image-export")]
+    Png,
+    /// A single animated `.gif` per part.
+    #[cfg(feature = "image-export")]
+    Gif,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(Format::Ppm),
+            #[cfg(feature = "image-export")]
+            "png" => Ok(Format::Png),
+            #[cfg(feature = "image-export")]
+            "gif" => Ok(Format::Gif),
+            #[cfg(not(feature = "image-export"))]
+            "png" | "gif" => Err(format!(
+                "{s} export requires the `image-export` feature, which `cargo solve` enables automatically"
+            )),
+            _ => Err(format!("unknown image format \"{s}\", expected ppm, png or gif")),
+        }
+    }
+}
+
+/// Where and how frames are written as images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Export {
+    pub format: Format,
+    pub cell_size: usize,
+    /// The time between frames of an animation.
+    pub delay: Duration,
+}
+
+impl Export {
+    pub const DEFAULT_CELL_SIZE: usize = 4;
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+    /// Writes the frames of a part to `data/visuals/<day>/`, returning the written files.
+    pub fn write(&self, frames: &[Frame], day: Day, part: u8) -> io::Result<Vec<PathBuf>> {
+        let dir = PathBuf::from("data").join("visuals").join(day.to_string());
+        fs::create_dir_all(&dir)?;
+
+        let palette = palette();
+        let images = frames
+            .iter()
+            .map(|frame| Image::render(frame, self.cell_size, &palette));
+
+        let extension = match self.format {
+            Format::Ppm => "ppm",
+            #[cfg(feature = "image-export")]
+            Format::Png => "png",
+            #[cfg(feature = "image-export")]
+            Format::Gif => {
+                let path = dir.join(format!("part-{part}.gif"));
+                let images: Vec<Image> = images.collect();
+                write_gif(&images, self.delay, BufWriter::new(File::create(&path)?))?;
+                return Ok(vec![path]);
+            }
+        };
+
+        let mut paths = vec![];
+        for (i, image) in images.enumerate() {
+            let path = dir.join(format!("part-{part}-{:04}.{extension}", i + 1));
+            let writer = BufWriter::new(File::create(&path)?);
+            match self.format {
+                Format::Ppm => image.write_ppm(writer)?,
+                #[cfg(feature = "image-export")]
+                Format::Png => image.write_png(writer)?,
+                #[cfg(feature = "image-export")]
+                Format::Gif => unreachable!(),
+            }
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Grid;
+
+    fn frame() -> Frame {
+        let grid: Grid<u8> = "#.\n.x".parse().unwrap();
+        Frame::new(&grid)
+            .highlight([(0, 1)], Color::Blue)
+            .marker((1, 0), '^', Color::Red)
+    }
+
+    #[test]
+    fn renders_cells_as_squares() {
+        let palette = Palette::default().with_char('x', [1, 2, 3]);
+        let image = Image::render(&frame(), 2, &palette);
+        assert_eq!((image.width, image.height), (4, 4));
+
+        let wall = [110, 110, 130];
+        let blue = palette.color(Color::Blue);
+        let red = palette.color(Color::Red);
+        let expected = [
+            [wall, wall, blue, blue],
+            [wall, wall, blue, blue],
+            [red, red, [1, 2, 3], [1, 2, 3]],
+            [red, red, [1, 2, 3], [1, 2, 3]],
+        ];
+        assert_eq!(image.pixels, expected.concat());
+
+        let recoloured = Palette::default().with_color(Color::Red, [9, 9, 9]);
+        assert_eq!(Image::render(&frame(), 1, &recoloured).pixels[2], [9, 9, 9]);
+    }
+
+    #[test]
+    fn writes_ppm() {
+        let image = Image::render(&Frame::blank(2, 1), 1, &Palette::default());
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert_eq!(ppm, b"P6\n2 1\n255\n\x0f\x0f\x23\x0f\x0f\x23");
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!("ppm".parse(), Ok(Format::Ppm));
+        assert!("bmp".parse::<Format>().is_err());
+        #[cfg(feature = "image-export")]
+        assert_eq!("gif".parse(), Ok(Format::Gif));
+        #[cfg(not(feature = "image-export"))]
+        assert!("png"
+            .parse::<Format>()
+            .unwrap_err()
+            .contains("image-export"));
+    }
+}
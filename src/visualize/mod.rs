@@ -0,0 +1,366 @@
+//! Terminal visualisation of grids, with overlays for paths, markers and highlighted cells.
+//!
+//! Solutions build [`Frame`]s and pass them to [`emit`]. Frames are only built and recorded when the solution runs
+//! with `--visualize`, in which case the runner plays them back after each part:
+//!
+//! ```ignore
+//! visualize::emit(|| Frame::new(&grid).path(visited.iter().copied(), Color::Green).marker(guard, '^', Color::Red));
+//! ```
+//!
+//! With `--export <ppm|png|gif>` the frames are written to `data/visuals/<day>/` as images instead, see [`image`].
+pub mod image;
+
+use std::{
+    fmt::{self, Display},
+    io::{stdin, stdout, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::grid::{Grid, GridCell};
+use crate::template::{ANSI_BOLD, ANSI_RESET};
+use image::{Export, Format};
+
+/// The eight basic terminal colours, plus gray.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    /// The SGR parameter that sets this colour as foreground. Add 10 for the background.
+    const fn sgr(self) -> u8 {
+        match self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::Gray => 90,
+        }
+    }
+}
+
+/// How a single cell of a [`Frame`] is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        if self.bold {
+            f.write_str(ANSI_BOLD)?;
+        }
+        if let Some(color) = self.foreground {
+            write!(f, "\x1b[{}m", color.sgr())?;
+        }
+        if let Some(color) = self.background {
+            write!(f, "\x1b[{}m", color.sgr() + 10)?;
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot of a grid with overlays. Overlays for positions outside the grid are ignored,
+/// so e.g. a guard that just left the map can be drawn without bounds checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    chars: Grid<char>,
+    styles: Grid<Style>,
+    caption: Option<String>,
+}
+
+impl Frame {
+    #[must_use]
+    pub fn new<T: GridCell>(grid: &Grid<T>) -> Self {
+        Self {
+            chars: grid.map(GridCell::to_char),
+            styles: Grid::new(grid.width(), grid.height(), Style::default()),
+            caption: None,
+        }
+    }
+
+    /// An empty frame filled with `.`, e.g. for puzzles that do not store a grid.
+    #[must_use]
+    pub fn blank(width: usize, height: usize) -> Self {
+        Self::new(&Grid::new(width, height, '.'))
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.chars.width()
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.chars.height()
+    }
+
+    /// The character and style of a cell.
+    #[must_use]
+    pub fn cell(&self, position: (usize, usize)) -> Option<(char, Style)> {
+        let (row, col) = position;
+        Some((*self.chars.get(row, col)?, *self.styles.get(row, col)?))
+    }
+
+    #[must_use]
+    pub fn caption_text(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    fn style_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut Style> {
+        self.styles.get_mut(row, col)
+    }
+
+    /// Colours the background of the given cells.
+    #[must_use]
+    pub fn highlight(
+        mut self,
+        positions: impl IntoIterator<Item = (usize, usize)>,
+        color: Color,
+    ) -> Self {
+        for position in positions {
+            if let Some(style) = self.style_mut(position) {
+                style.background = Some(color);
+            }
+        }
+        self
+    }
+
+    /// Draws the characters of the given cells in a bold colour.
+    #[must_use]
+    pub fn path(
+        mut self,
+        positions: impl IntoIterator<Item = (usize, usize)>,
+        color: Color,
+    ) -> Self {
+        for position in positions {
+            if let Some(style) = self.style_mut(position) {
+                style.foreground = Some(color);
+                style.bold = true;
+            }
+        }
+        self
+    }
+
+    /// Replaces the character of a cell, drawn in a bold colour.
+    #[must_use]
+    pub fn marker(mut self, position: (usize, usize), symbol: char, color: Color) -> Self {
+        if let Some(cell) = self.chars.get_mut(position.0, position.1) {
+            *cell = symbol;
+            self = self.path([position], color);
+        }
+        self
+    }
+
+    /// A line of text shown below the grid.
+    #[must_use]
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+}
+
+/// Renders the frame with ANSI escape codes, followed by the caption on its own line.
+impl Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row, chars) in self.chars.rows().enumerate() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            let mut current = Style::default();
+            for (&ch, style) in chars.iter().zip(self.styles.row(row)) {
+                if *style != current {
+                    if current != Style::default() {
+                        f.write_str(ANSI_RESET)?;
+                    }
+                    style.write_ansi(f)?;
+                    current = *style;
+                }
+                write!(f, "{ch}")?;
+            }
+            if current != Style::default() {
+                f.write_str(ANSI_RESET)?;
+            }
+        }
+        if let Some(caption) = &self.caption {
+            write!(f, "\n{caption}")?;
+        }
+        Ok(())
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FRAMES: Mutex<Vec<Frame>> = Mutex::new(vec![]);
+
+/// Whether frames are currently being recorded.
+#[must_use]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Starts or stops recording frames. Called by the runner, solutions only [`emit`].
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Records a frame. The closure is only called when visualisation is enabled, so frames cost nothing otherwise.
+pub fn emit(frame: impl FnOnce() -> Frame) {
+    if is_enabled() {
+        let frame = frame();
+        FRAMES.lock().unwrap().push(frame);
+    }
+}
+
+/// Removes and returns all recorded frames.
+pub fn take_frames() -> Vec<Frame> {
+    std::mem::take(&mut *FRAMES.lock().unwrap())
+}
+
+/// How recorded frames are played back in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Playback {
+    /// The time between frames, or `None` to wait for <Enter> after every frame.
+    pub delay: Option<Duration>,
+    /// Write the frames as images instead of drawing them in the terminal.
+    pub export: Option<Export>,
+}
+
+impl Playback {
+    /// Reads the `--visualize`, `--frame-delay <ms>`, `--export <format>` and `--cell-size <px>` arguments
+    /// passed to a solution binary.
+    ///
+    /// # Errors
+    /// Returns an error for an unknown or disabled export format.
+    pub fn from_args() -> Result<Option<Self>, String> {
+        let args: Vec<String> = std::env::args().collect();
+        if !args.iter().any(|x| x == "--visualize") {
+            return Ok(None);
+        }
+        let value = |name: &str| {
+            let index = args.iter().position(|x| x == name)?;
+            args.get(index + 1)
+        };
+        let delay = value("--frame-delay")
+            .and_then(|ms| ms.parse().ok())
+            .map(Duration::from_millis);
+        let export = match value("--export") {
+            Some(format) => Some(Export {
+                format: format.parse::<Format>()?,
+                cell_size: value("--cell-size")
+                    .and_then(|px| px.parse().ok())
+                    .filter(|&px| px > 0)
+                    .unwrap_or(Export::DEFAULT_CELL_SIZE),
+                delay: delay.unwrap_or(Export::DEFAULT_DELAY),
+            }),
+            None => None,
+        };
+        Ok(Some(Self { delay, export }))
+    }
+
+    /// Clears the terminal and draws every frame in turn. When stepping through frames,
+    /// entering `q` skips the remaining ones.
+    pub fn play(&self, frames: &[Frame]) {
+        let mut stdout = stdout();
+        for (i, frame) in frames.iter().enumerate() {
+            print!("\x1b[2J\x1b[H{frame}\n\nFrame {}/{}", i + 1, frames.len());
+            match self.delay {
+                Some(delay) => {
+                    println!();
+                    let _ = stdout.flush();
+                    thread::sleep(delay);
+                }
+                None => {
+                    print!(" - <Enter> for the next frame, q to skip ");
+                    let _ = stdout.flush();
+                    let mut line = String::new();
+                    if stdin().read_line(&mut line).is_err() || line.trim() == "q" {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<u8> {
+        "..#\n#..\n".parse().unwrap()
+    }
+
+    #[test]
+    fn renders_plain_grids() {
+        let frame = Frame::new(&grid());
+        assert_eq!(frame.to_string(), grid().to_string());
+        assert_eq!(
+            Frame::blank(2, 1).caption("step 1").to_string(),
+            "..\nstep 1"
+        );
+    }
+
+    #[test]
+    fn renders_overlays() {
+        let frame = Frame::new(&grid())
+            .highlight([(0, 0), (5, 5)], Color::Blue)
+            .path([(0, 0), (0, 1)], Color::Green)
+            .marker((1, 1), '^', Color::Red)
+            .marker((9, 9), 'x', Color::Red);
+
+        assert_eq!(
+            frame.to_string(),
+            format!(
+                "{ANSI_BOLD}\x1b[32m\x1b[44m.{ANSI_RESET}{ANSI_BOLD}\x1b[32m.{ANSI_RESET}#\n\
+                 #{ANSI_BOLD}\x1b[31m^{ANSI_RESET}."
+            )
+        );
+        assert_eq!(
+            frame.cell((1, 1)),
+            Some((
+                '^',
+                Style {
+                    foreground: Some(Color::Red),
+                    background: None,
+                    bold: true
+                }
+            ))
+        );
+        assert_eq!(frame.cell((2, 0)), None);
+    }
+
+    #[test]
+    fn records_frames_only_when_enabled() {
+        emit(|| unreachable!("frames are not built while disabled"));
+
+        set_enabled(true);
+        emit(|| Frame::blank(1, 1));
+        emit(|| Frame::blank(2, 2));
+        set_enabled(false);
+        emit(|| unreachable!());
+
+        let frames = take_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].width(), 2);
+        assert!(take_frames().is_empty());
+    }
+}